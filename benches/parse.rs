@@ -0,0 +1,211 @@
+// times float parsing on nucleotide lines of a representative frame, with str::parse and the enabled backend,
+// then reading whole configurations with the backend the crate was built with, and counts allocations of
+// parsing a large frame into pre-sized vectors against collecting without a size hint
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use oxdna_trajectory_reader::{read_confs, Configuration, ParseOptions};
+
+const NUCLEOTIDES: usize = 20_000;
+const FRAMES: usize = 10;
+const RUNS: usize = 10;
+const LARGE_FRAME_NUCLEOTIDES: usize = 200_000;
+
+// counts allocations and reallocations, i.e. every time a vector grows
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn count_allocations<T>(run: impl FnOnce() -> T) -> (usize, Duration, T) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let result = run();
+    let elapsed = start.elapsed();
+    (
+        ALLOCATIONS.load(Ordering::Relaxed) - before,
+        elapsed,
+        result,
+    )
+}
+
+fn backend() -> &'static str {
+    if cfg!(feature = "fast_float") {
+        "fast_float"
+    } else if cfg!(feature = "lexical") {
+        "lexical"
+    } else {
+        "str::parse"
+    }
+}
+
+// 6 significant digits without trailing zeros, as oxDNA writes values
+fn format_value(value: f64) -> String {
+    let magnitude = value.abs().log10().floor() as i32;
+    let decimals = (5 - magnitude).max(0) as usize;
+    let text = format!("{:.*}", decimals, value);
+    match text.contains('.') {
+        true => text.trim_end_matches('0').trim_end_matches('.').to_string(),
+        false => text,
+    }
+}
+
+// lines of one configuration with 15 values per nucleotide
+fn frame_lines(time: usize, n_nucleotides: usize) -> Vec<String> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+    };
+    let mut lines = vec![
+        format!("t = {}", time),
+        "b = 100 100 100".to_string(),
+        "E = -1.5823 -1.60104 0.0186828".to_string(),
+    ];
+    for _ in 0..n_nucleotides {
+        let values = (0..15)
+            .map(|column| match column {
+                0..=2 => format_value(next() * 50.0),
+                _ => format_value(next()),
+            })
+            .collect::<Vec<_>>();
+        lines.push(values.join(" "));
+    }
+    lines
+}
+
+fn best_of(mut run: impl FnMut()) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .min()
+        .expect("runs at least once")
+}
+
+fn time_tokens(name: &str, tokens: &[&str], parse: impl Fn(&str) -> Option<f64>) -> Duration {
+    let elapsed = best_of(|| {
+        for token in tokens {
+            black_box(parse(black_box(token)));
+        }
+    });
+    println!(
+        "{:<12} {:>8.2} ns/value",
+        name,
+        elapsed.as_nanos() as f64 / tokens.len() as f64
+    );
+    elapsed
+}
+
+fn main() {
+    let lines = frame_lines(0, NUCLEOTIDES);
+    let tokens = lines[3..]
+        .iter()
+        .flat_map(|line| line.split_whitespace())
+        .collect::<Vec<_>>();
+    println!("parsing {} values", tokens.len());
+    let baseline = time_tokens("str::parse", &tokens, |token| token.parse().ok());
+    #[cfg(feature = "fast_float")]
+    {
+        let elapsed = time_tokens("fast_float", &tokens, |token| {
+            fast_float2::parse(token).ok()
+        });
+        println!(
+            "fast_float takes {:.0}% of str::parse",
+            100.0 * elapsed.as_secs_f64() / baseline.as_secs_f64()
+        );
+    }
+    #[cfg(feature = "lexical")]
+    {
+        let elapsed = time_tokens("lexical", &tokens, |token| {
+            lexical_core::parse(token.as_bytes()).ok()
+        });
+        println!(
+            "lexical takes {:.0}% of str::parse",
+            100.0 * elapsed.as_secs_f64() / baseline.as_secs_f64()
+        );
+    }
+    #[cfg(not(any(feature = "fast_float", feature = "lexical")))]
+    let _ = baseline;
+
+    let path = std::env::temp_dir().join(format!("oxdna_parse_bench_{}.dat", std::process::id()));
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&path).expect("temporary file"));
+    for time in 0..FRAMES {
+        for line in frame_lines(time, NUCLEOTIDES) {
+            writeln!(file, "{}", line).expect("temporary file");
+        }
+    }
+    drop(file);
+    let path_str = path.to_str().expect("temporary path is UTF-8");
+    let options = ParseOptions::default();
+    let elapsed = best_of(|| {
+        let configs = read_confs(path_str, 0, FRAMES, &options).expect("generated trajectory");
+        assert_eq!(configs.len(), FRAMES);
+        black_box(configs);
+    });
+    println!(
+        "read_confs   {:>8.2} ms for {} frames of {} nucleotides with {}",
+        elapsed.as_secs_f64() * 1000.0,
+        FRAMES,
+        NUCLEOTIDES,
+        backend()
+    );
+    std::fs::remove_file(&path).expect("temporary file");
+
+    // nucleotide vectors as they were built before pre-sizing, collected from iterators over Result
+    let lines = frame_lines(0, LARGE_FRAME_NUCLEOTIDES);
+    let (unsized_allocations, unsized_elapsed, nucleotides) = count_allocations(|| {
+        lines[3..]
+            .iter()
+            .map(|line| {
+                line.split_whitespace()
+                    .map(str::parse::<f64>)
+                    .collect::<Result<Vec<f64>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .expect("generated values")
+    });
+    drop(black_box(nucleotides));
+    let frame = lines.clone();
+    let (sized_allocations, sized_elapsed, conf) = count_allocations(|| {
+        Configuration::from_lines(frame, 0, &options).expect("generated frame")
+    });
+    drop(black_box(conf));
+    println!(
+        "collected    {:>8} allocations {:>8.2} ms for a frame of {} nucleotides with str::parse",
+        unsized_allocations,
+        unsized_elapsed.as_secs_f64() * 1000.0,
+        LARGE_FRAME_NUCLEOTIDES
+    );
+    println!(
+        "from_lines   {:>8} allocations {:>8.2} ms with pre-sized vectors and {}",
+        sized_allocations,
+        sized_elapsed.as_secs_f64() * 1000.0,
+        backend()
+    );
+}
@@ -1,15 +1,142 @@
-from .oxdna_trajectory_reader import read_configurations, read_indicies
-from .configuration import Configuration, ConfigurationSlice, Nucleotide
+from .oxdna_trajectory_reader import (
+    read_configurations,
+    read_configurations_by_index,
+    read_configurations_parallel,
+    read_frames,
+    read_last,
+    read_lammps_configurations,
+    find_configuration_by_time,
+    read_configurations_from_stdin,
+    iter_configurations,
+    read_configurations_multi,
+    read_configurations_multi_parallel,
+    read_raw_lines,
+    read_indicies,
+    read_indicies_mmap,
+    build_index,
+    save_index,
+    load_index,
+    truncate_incomplete,
+    set_num_threads,
+    get_num_threads,
+    time_deltas,
+    read_boxes,
+    read_headers,
+    validate_trajectory,
+    read_topology,
+    read_energies,
+    verify_checksums,
+    FrameView,
+    TrajectoryReader,
+    TrajectoryWriter,
+    ReadOptions,
+    ReadResult,
+    ValidationSummary,
+    TrajectoryFormatError,
+    MissingHeaderError,
+    BadValueError,
+    CountMismatchError,
+    centers_of_mass,
+    temperature,
+    strand_centers,
+    read_rmsd,
+    end_to_end_vectors,
+    read_displacements,
+    read_pair_distances,
+    clash_count,
+    read_position_statistics,
+    read_position_bounds,
+    oriented_bbox,
+    p2_order,
+    equilibration_frame,
+    check_energy_sum,
+    find_out_of_box,
+    orientation_norm_range,
+    count_mode,
+    append_configuration,
+    downsample_trajectory,
+    merge_trajectories_by_time,
+    write_positions_csv,
+    write_configurations_xyz,
+    read_configurations_mmap_out,
+)
+from .configuration import Configuration, ConfigurationSlice, Nucleotide, to_pdb, to_lammps_data
 from .trajectory import Trajectory
 from .topology import Topology
+from .system import System, StrandSlice
+from .oxview import to_oxview, read_oxview
 
 
 __all__ = [
     'read_configurations',
+    'read_configurations_by_index',
+    'read_configurations_parallel',
+    'read_frames',
+    'read_last',
+    'read_lammps_configurations',
+    'find_configuration_by_time',
+    'read_configurations_from_stdin',
+    'iter_configurations',
+    'read_configurations_multi',
+    'read_configurations_multi_parallel',
+    'read_raw_lines',
     'read_indicies',
+    'read_indicies_mmap',
+    'build_index',
+    'save_index',
+    'load_index',
+    'truncate_incomplete',
+    'set_num_threads',
+    'get_num_threads',
+    'time_deltas',
+    'read_boxes',
+    'read_headers',
+    'validate_trajectory',
+    'read_topology',
+    'read_energies',
+    'verify_checksums',
+    'centers_of_mass',
+    'temperature',
+    'strand_centers',
+    'read_rmsd',
+    'end_to_end_vectors',
+    'read_displacements',
+    'read_pair_distances',
+    'clash_count',
+    'read_position_statistics',
+    'read_position_bounds',
+    'oriented_bbox',
+    'p2_order',
+    'equilibration_frame',
+    'check_energy_sum',
+    'find_out_of_box',
+    'orientation_norm_range',
+    'count_mode',
+    'append_configuration',
+    'downsample_trajectory',
+    'merge_trajectories_by_time',
+    'write_positions_csv',
+    'write_configurations_xyz',
+    'read_configurations_mmap_out',
+    'FrameView',
+    'TrajectoryReader',
+    'TrajectoryWriter',
+    'ReadOptions',
+    'ReadResult',
+    'ValidationSummary',
+    'TrajectoryFormatError',
+    'MissingHeaderError',
+    'BadValueError',
+    'CountMismatchError',
     'Configuration',
     'ConfigurationSlice',
     'Nucleotide',
+    'to_pdb',
+    'to_lammps_data',
     'Trajectory',
-    'Topology'
+    'Topology',
+    'System',
+    'StrandSlice',
+    'to_oxview',
+    'read_oxview'
 ]
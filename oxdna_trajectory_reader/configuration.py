@@ -5,6 +5,18 @@ import typing
 import numpy as np
 
 from .oxdna_trajectory_reader import dumps_configurations as _dumps_configurations
+from .oxdna_trajectory_reader import dumps_configurations_positions as _dumps_configurations_positions
+from .oxdna_trajectory_reader import dumps_configurations_xyz as _dumps_configurations_xyz
+from .oxdna_trajectory_reader import dumps_configurations_lammps as _dumps_configurations_lammps
+from .oxdna_trajectory_reader import write_configurations as _write_configurations
+from .oxdna_trajectory_reader import write_configurations_pdb as _write_configurations_pdb
+from .oxdna_trajectory_reader import write_configuration_lammps_data as _write_configuration_lammps_data
+
+
+def _check_configurations(configurations: list[Configuration]):
+    if not all(isinstance(c, Configuration) for c in configurations):
+        other_types = set(type(c).__name__ for c in configurations if not isinstance(c, Configuration))
+        raise TypeError(f'All elements in configurations must be Configuration, not {", ".join(other_types)}')
 
 
 def dumps_configurations(configurations: list[Configuration]) -> list[str]:
@@ -14,12 +26,110 @@ def dumps_configurations(configurations: list[Configuration]) -> list[str]:
     :param configurations: List of `Configuration`
     :return: List of string per configuration
     """
-    if not all(isinstance(c, Configuration) for c in configurations):
-        other_types = set(type(c).__name__ for c in configurations if not isinstance(c, Configuration))
-        raise TypeError(f'All elements in configurations must be Configuration, not {", ".join(other_types)}')
+    _check_configurations(configurations)
     return _dumps_configurations([(c.time, c.box, c.energy, c._nucleotides) for c in configurations])
 
 
+def dumps_configurations_positions(configurations: list[Configuration]) -> list[str]:
+    """
+    Convert `Configuration`s back to strings using trajectory file format, keeping only nucleotide positions
+
+    :param configurations: List of `Configuration`
+    :return: List of string per configuration
+    """
+    _check_configurations(configurations)
+    return _dumps_configurations_positions([(c.time, c.box, c.energy, c._nucleotides) for c in configurations])
+
+
+def dumps_configurations_xyz(configurations: list[Configuration], element: str = 'C',
+                             topology: Topology | None = None) -> list[str]:
+    """
+    Convert `Configuration`s to strings using XYZ file format, one `element` line per nucleotide position
+
+    :param configurations: List of `Configuration`
+    :param element: Element label for every nucleotide
+    :param topology: `Topology` of the configurations, to label each nucleotide with its base instead
+        like `write_configurations_xyz`
+    :return: List of string per configuration
+    """
+    _check_configurations(configurations)
+    bases = None
+    if topology is not None:
+        bases = [base for strand in sorted(topology, key=lambda strand: strand.start) for base in strand.sequence]
+    return _dumps_configurations_xyz([(c.time, c.box, c.energy, c._nucleotides) for c in configurations], element,
+                                     bases)
+
+
+def dumps_configurations_lammps(configurations: list[Configuration],
+                                box_origin: tuple[float, float, float] = (0.0, 0.0, 0.0)) -> list[str]:
+    """
+    Convert `Configuration`s to strings using LAMMPS dump file format, one atom per nucleotide position
+
+    :param configurations: List of `Configuration`
+    :param box_origin: Lower box bounds, upper bounds are `box_origin + box`
+    :return: List of string per configuration
+    """
+    _check_configurations(configurations)
+    return _dumps_configurations_lammps([(c.time, c.box, c.energy, c._nucleotides) for c in configurations],
+                                        box_origin)
+
+
+def write_configurations(file_path: str, configurations: list[Configuration], append: bool = False) -> list[int]:
+    """
+    Write `Configuration`s to a file using trajectory file format, serializing them in parallel
+
+    :param file_path: Path to output trajectory file
+    :param configurations: List of `Configuration`
+    :param append: Append to the file instead of overwriting it
+    :return: End offset of each written configuration
+    """
+    _check_configurations(configurations)
+    return _write_configurations(file_path, [(c.time, c.box, c.energy, c._nucleotides) for c in configurations],
+                                 append)
+
+
+def to_pdb(configurations: list[Configuration], topology: Topology, out_path: str, sites: str = 'nucleotide'):
+    """
+    Write `Configuration`s to a PDB file for viewing in ChimeraX or PyMOL, one model per configuration and one chain
+    per strand of `topology`, bonded along the strand by CONECT records. Coordinates are converted to angstrom
+
+    :param configurations: List of `Configuration`
+    :param topology: `Topology` of the configurations
+    :param out_path: Path to output PDB file
+    :param sites: 'nucleotide' for one pseudo-atom per nucleotide at its position, or 'backbone_base' for
+        a backbone atom at `backbone_center_positions` and a base atom at `base_center_positions`
+    """
+    _check_configurations(configurations)
+    if sites == 'nucleotide':
+        site_arrays = [c.positions for c in configurations]
+    elif sites == 'backbone_base':
+        site_arrays = [np.stack([c.backbone_center_positions, c.base_center_positions], axis=1).reshape(-1, 3)
+                       for c in configurations]
+    else:
+        raise ValueError(f"Invalid sites '{sites}', expected 'nucleotide' or 'backbone_base'")
+    strands = [(strand.start, strand.end, strand.sequence)
+               for strand in sorted(topology, key=lambda strand: strand.start)]
+    _write_configurations_pdb(out_path, [(c.time, c.box, c.energy, np.ascontiguousarray(site_array))
+                                         for c, site_array in zip(configurations, site_arrays)],
+                              strands, 1 if sites == 'nucleotide' else 2)
+
+
+def to_lammps_data(configuration: Configuration, topology: Topology, out_path: str):
+    """
+    Write a `Configuration` and its topology as a LAMMPS data file to start a run of the LAMMPS oxdna package,
+    with an ellipsoid atom per nucleotide, a bond along each strand, and orientations as quaternions
+
+    :param configuration: `Configuration` to write
+    :param topology: `Topology` of the configuration
+    :param out_path: Path to output data file
+    """
+    _check_configurations([configuration])
+    strands = [(strand.start, strand.end, strand.sequence)
+               for strand in sorted(topology, key=lambda strand: strand.start)]
+    _write_configuration_lammps_data(out_path, (configuration.time, configuration.box, configuration.energy,
+                                                configuration._nucleotides), strands)
+
+
 def readonly(func):
     @functools.wraps(func)
     def wrapper(*args, **kwargs):
@@ -382,3 +492,4 @@ class Nucleotide:
 
 if typing.TYPE_CHECKING:
     import numpy.typing as npt
+    from .topology import Topology
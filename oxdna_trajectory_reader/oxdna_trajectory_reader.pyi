@@ -1,41 +1,975 @@
 from __future__ import annotations
+import typing
+from typing import Callable, Iterator
 import numpy as np
 import numpy.typing as npt
 
 
-def read_configurations(file_path: str, offset: int, limit: int) -> tuple[list[int], list[tuple[
-    int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]
+class TrajectoryFormatError(ValueError):
+    """
+    Malformed trajectory content, base of the errors raised when a configuration cannot be parsed
+    """
+    index: int | None
+    """
+    Position of the configuration among those read, counting from the first one read, None if unknown
+    """
+    line_number: int | None
+    """
+    1-based line number in the file, None when reading a compressed file, a file-like object or stdin
+    """
+    offset: int | None
+    """
+    Byte offset of the line in the file, None if unknown
+    """
+
+
+class MissingHeaderError(TrajectoryFormatError):
+    """
+    A header line is missing or does not start with the expected key
+    """
+    header: str
+    """
+    Name of the header, 'time', 'box' or 'energy'
+    """
+    line: str | None
+    """
+    The line found in place of the header, None if the configuration ended before it
+    """
+
+
+class BadValueError(TrajectoryFormatError):
+    """
+    A value could not be parsed as a number
+    """
+    frame: int | None
+    """
+    Time of the configuration, None if the time header itself is invalid
+    """
+    line: str
+    value: str
+
+
+class CountMismatchError(TrajectoryFormatError):
+    """
+    A line has a different number of values than expected, e.g. a nucleotide line of a truncated configuration
+    """
+    frame: int | None
+    name: str
+    """
+    What is counted, e.g. 'box' or 'nucleotide'
+    """
+    line: str | None
+    expected: int
+    found: int
+
+
+class ReadOptions:
+    """
+    Options of `read_configurations`, reusable across calls. Takes each option as a keyword argument,
+    see `read_configurations` for what they do and their defaults, raising TypeError for an unknown one
+    """
+    case_insensitive_headers: bool
+    nucleotide_range: tuple[int, int] | None
+    require_frame_start: bool
+    reject_nonfinite: bool
+    columns_per_nucleotide: int | None
+    end_offset: int | None
+    strand_ids: list[int] | None
+    header_position: str
+    strict: bool
+    strict_energy_count: int | None
+    check_energy_count: bool
+    validate_box: bool
+    stride: int
+    stacked: bool
+    split_columns: bool
+    dtype: str
+    extra_columns: bool
+    errors: str
+
+    def __init__(
+        self, *, case_insensitive_headers: bool = False, nucleotide_range: tuple[int, int] | None = None,
+        require_frame_start: bool = False, reject_nonfinite: bool = False, columns_per_nucleotide: int | None = None,
+        end_offset: int | None = None, strand_ids: list[int] | None = None, header_position: str = 'leading',
+        strict: bool = False, strict_energy_count: int | None = None, check_energy_count: bool = True,
+        validate_box: bool = False, stride: int = 1, stacked: bool = False, split_columns: bool = False,
+        dtype: str = 'float64', extra_columns: bool = False, errors: str = 'raise'
+    ) -> None:
+        ...
+
+
+class ReadResult:
+    """
+    Result of `read_configurations`, unpacks and indexes as a tuple of `end_offsets` and `configurations`
+    """
+    end_offsets: list[int]
+    configurations: list[tuple[typing.Any, ...]] | tuple[typing.Any, ...]
+    """
+    List of configurations, or a tuple of times, boxes, energies and nucleotides arrays if read with `stacked`,
+    see `read_configurations`
+    """
+    bytes_read: int
+    """
+    Number of bytes read from `offset`, which includes the first line of the configuration after the last one
+    read unless reading stopped at end of file, so it can exceed `end_offsets[-1] - offset`
+    """
+    discarded_partial: bool
+    """
+    Whether a configuration cut off at end of file was dropped, see `strict` of `read_configurations`
+    """
+    energy_count: int | None
+    """
+    Number of energy values of every returned configuration, None if no configuration was read or, with
+    `check_energy_count=False`, if configurations have different numbers of energy values
+    """
+    skipped: list[tuple[int, str]]
+    """
+    Index from `offset` and error message of each configuration dropped with `errors='skip'`
+    """
+
+    def __iter__(self) -> Iterator[typing.Any]:
+        ...
+
+    def __len__(self) -> int:
+        ...
+
+    def __getitem__(self, index: int) -> typing.Any:
+        ...
+
+
+def read_configurations(
+    file_path: str | typing.BinaryIO | typing.TextIO, offset: int, limit: int, options: ReadOptions | None = None,
+    progress: Callable[[int, int], object] | None = None, *, case_insensitive_headers: bool = False,
+    nucleotide_range: tuple[int, int] | None = None, require_frame_start: bool = False,
+    reject_nonfinite: bool = False, columns_per_nucleotide: int | None = None, end_offset: int | None = None, strand_ids: list[int] | None = None,
+    header_position: str = 'leading', strict: bool = False, strict_energy_count: int | None = None,
+    check_energy_count: bool = True, validate_box: bool = False, stride: int = 1, stacked: bool = False,
+    split_columns: bool = False, dtype: str = 'float64', extra_columns: bool = False, errors: str = 'raise'
+) -> ReadResult:
+    """
+    Read up to number of `limit` configurations from trajectory, starting from file cursor `offset`
+
+    :param file_path: Path to trajectory file, or a file-like object with a `read` method, e.g. a remote file,
+        preferably in binary mode as text is counted in UTF-8 bytes. Its `seek` is used if it is seekable,
+        otherwise `offset` bytes are skipped from its current position. Lines are read on the calling thread
+        and only parsing is parallel. A gzip, bz2, xz or zstd compressed file, detected by its magic bytes,
+        is read the same way through `gzip`, `bz2`, `lzma`, or `compression.zstd` (Python 3.14+) falling back to
+        the `zstandard` package, with offsets in the decompressed stream. Seeking decompresses from the start
+        of the file, so reading at a large offset costs as much as reading up to it, except for zstd files in the
+        seekable format, e.g. written by `t2sz`, whose seek table lets reading start at the
+        frame containing `offset`. Every other reader raises
+        ValueError for a compressed file, as its offsets would not match these
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param options: `ReadOptions` holding the keyword-only options below, each of which overrides its value
+        in `options` when also given
+    :param progress: Called with the cursor offset at end of the last read configuration and the number of
+        configurations read so far, after every 256 configurations and at the end,
+        exceptions raised by it stop reading and propagate
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :param nucleotide_range: Only parse nucleotides in `[start, end)` of each configuration,
+        raises ValueError if a configuration has fewer than `end` nucleotides
+    :param require_frame_start: Raise ValueError if `offset` is not at a `t =` line, otherwise an `offset`
+        inside a configuration skips forward to the next configuration
+    :param reject_nonfinite: Raise ValueError naming the configuration, nucleotide and column
+        if a nucleotide value is `nan` or `inf`, otherwise they are returned as is
+    :param columns_per_nucleotide: Number of values per nucleotide line, e.g. 9 for files without velocities,
+        raises ValueError stating expected and found count for a line with a different number of values.
+        None takes 9 if the first nucleotide line of a configuration has 9 values and 15 otherwise,
+        separately for each configuration
+    :param end_offset: Stop before a configuration starting at or after this file cursor offset, so byte ranges
+        `[a, b)` and `[b, c)` read every configuration exactly once, a configuration starting before
+        `end_offset` is read whole even if it extends past it
+    :param strand_ids: Strand id of each nucleotide, e.g. from a topology file, to return nucleotide vectors
+        as a dict of strand id to nucleotide vectors of that strand in file order,
+        raises ValueError if its length differs from the number of nucleotides of a configuration
+    :param header_position: `leading` if `t`, `b`, `E` lines start each configuration, or `trailing` if
+        they end it after the nucleotide lines, then `offset` must be at the first nucleotide line of
+        a configuration and `require_frame_start` does not apply
+    :param strict: Treat a last configuration cut off at end of file, e.g. by a killed simulation, like any other,
+        returning it or raising ValueError if it fails to parse, otherwise it is dropped and `discarded_partial` is set.
+        A configuration reaching end of file is cut off if it fails to parse, e.g. with a header or number cut off,
+        or if it has fewer nucleotides than the configuration before it, the same as `truncate_incomplete` detects
+    :param strict_energy_count: Number of energy values every configuration must have instead of the count of
+        the first configuration read, even with `check_energy_count=False`
+    :param check_energy_count: Require every configuration to have as many energy values as
+        `strict_energy_count`, or as the first configuration read if not given. Raises ValueError naming the index
+        from `offset` of the first configuration with a different count, the count used is returned as
+        `energy_count`. False reads files mixing counts, each configuration's energy having as many values
+        as its energy line
+    :param validate_box: Raise ValueError naming the configuration time and value if a box edge is zero, negative
+        or not finite, which usually means a corrupt header, otherwise box values are returned as is
+    :param stride: Read every `stride`-th configuration starting with the first one, configurations in between
+        are only scanned for where the next one starts and not parsed. `limit` counts returned configurations
+        and `end_offsets` are those of returned configurations
+    :param stacked: Return configurations as one tuple of arrays instead of a list of tuples, saving a few numpy
+        objects per configuration: times of shape `(n_frames,)`, boxes `(n_frames, 3)`, energies
+        `(n_frames, energy_count)` and nucleotides `(n_frames, n_nucleotides, columns_per_nucleotide)`.
+        Raises ValueError if configurations have different numbers of nucleotides or columns, or with `strand_ids`
+    :param split_columns: Return nucleotide vectors as a dict of `positions`, `a1`, `a3`, `velocities` and
+        `angular_velocities` arrays of 3 columns each, as many as the configuration has, e.g. only the
+        first three for 9 columns. Stacked arrays are split the same way along their last axis.
+        Raises ValueError if `columns_per_nucleotide` is not a multiple of 3 up to 15, or with `strand_ids`
+    :param dtype: `float64` or `float32`, element type of nucleotide arrays, `float32` halves their memory.
+        Values are parsed as float64 and rounded to the nearest float32, times, boxes and energies stay float64
+    :param extra_columns: Accept nucleotide lines with values after the standard ones, e.g. observables appended by
+        a patched oxDNA, and return them as a fifth element of each configuration of shape `(n_nucleotides, k)`,
+        a fifth stacked array of shape `(n_frames, n_nucleotides, k)`, or an `extra` entry with `split_columns`.
+        Standard columns are `columns_per_nucleotide` or 15, every line of a configuration must have as many
+        values as its first one. Raises ValueError with `strand_ids`
+    :param errors: `raise` to raise on the first configuration that fails to parse, or `skip` to drop it and
+        continue at the next `t =` line, listing it in `skipped`. `limit` counts skipped configurations too
+    :return: `ReadResult` with list of cursor offsets at end of each configuration, list of configurations,
+        and number of bytes read, unpacking as `end_offsets, configurations`. Each configuration is a tuple of
+        - Time
+        - Box dimensions
+        - Energy, as many values as the frame's energy line has
+        - Nucleotide vectors, 9 or 15 values each or `columns_per_nucleotide` if given, grouped by strand
+          if `strand_ids` is given
+
+    The end offset of a configuration is the start offset of the next one, or the file size for the last one,
+    whether or not the file ends with a newline. Passing the last returned end offset as `offset` continues
+    reading with no gap or overlap, so reading `limit=5` twice gives the same configurations as `limit=10`.
+    """
+    ...
+
+
+def read_configurations_by_index(
+    file_path: str, offsets: list[int], start_index: int, count: int, case_insensitive_headers: bool = False
+) -> tuple[list[int], list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]]:
+    """
+    Read `count` configurations starting from configuration index `start_index` of a table of start offsets
+
+    :param file_path: Path to trajectory file
+    :param offsets: File cursor offsets at start of each configuration, e.g. `[0] + ends[:-1]` for end offsets
+        `ends` returned by `read_indicies(file_path, 0, n)`. Reading seeks to `offsets[start_index]`
+    :param start_index: Index in `offsets` of the first configuration to read
+    :param count: Number of configurations to read
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Tuple of list of cursor offsets at end of each configuration, and list of configurations,
+        same as `read_configurations`, raises ValueError if `start_index + count` exceeds the length of `offsets`
+        or the start of configuration `start_index` is not at a `t =` line
+    """
+    ...
+
+
+def read_configurations_parallel(
+    file_path: str, offsets: list[int], limit: int, case_insensitive_headers: bool = False
+) -> tuple[list[int], list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]]:
+    """
+    Read configurations at up to `limit` start offsets, splitting offsets into chunks that are read and parsed
+    in parallel, each from its own file handle
+
+    :param file_path: Path to trajectory file
+    :param offsets: File cursor offsets of configuration starts, in any order, configurations are returned in the
+        order of `offsets`. Unlike the end offsets of `read_indicies`, these are where configurations begin, e.g.
+        `[0, *read_indicies(file_path, 0, n)[:-1]]`
+    :param limit: Read configurations at up to the first `limit` offsets
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Tuple of list of cursor offsets at end of each configuration, and list of configurations,
+        same as `read_configurations`, raises ValueError if an offset is not at a `t =` line
+    """
+    ...
+
+
+def read_frames(
+    file_path: str, indices: list[int], case_insensitive_headers: bool = False
+) -> tuple[list[int], list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]]:
+    """
+    Read configurations at arbitrary indicies, e.g. a bootstrap or cluster sample, scanning the file up to
+    the largest index without parsing and then parsing only the requested configurations in parallel.
+    With start offsets already at hand, `read_configurations_parallel` avoids the scan
+
+    :param file_path: Path to trajectory file
+    :param indices: Indicies of configurations counted from the start of the file, in any order and possibly
+        repeated, configurations are returned in the order of `indices`
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Tuple of list of cursor offsets at end of each configuration, and list of configurations,
+        same as `read_configurations`, raises ValueError if an index is not less than the number of configurations
+    """
+    ...
+
+
+def read_last(
+    file_path: str, n: int, case_insensitive_headers: bool = False
+) -> tuple[list[int], list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]]:
+    """
+    Read the last `n` configurations, e.g. the final state of a long simulation, finding where they start by
+    scanning backward from end of file for `t =` lines instead of reading the whole file
+
+    :param file_path: Path to trajectory file
+    :param n: Number of configurations to read, all of them if the file has fewer
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Tuple of list of cursor offsets at end of each configuration, and list of configurations,
+        same as `read_configurations`
+    """
+    ...
+
+
+def read_lammps_configurations(
+    file_path: str, offset: int, limit: int
+) -> tuple[list[int], list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]]:
+    """
+    Read configurations from a dump file of the LAMMPS oxdna package, converting orientation quaternions
+    to a1 and a3 vectors, so LAMMPS and oxDNA simulations are analysed alike. The ATOMS section needs `id`,
+    `x y z` or `xu yu zu`, and `quatw quati quatj quatk` or a 4 column quaternion compute such as `c_quat[1]`
+    to `c_quat[4]`; `ix iy iz` image flags unwrap `x y z`, and `vx vy vz` and `angmomx angmomy angmomz`
+    fill the last 6 columns, which are left out if neither is dumped
+
+    :param file_path: Path to LAMMPS dump file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of timesteps, or until end of file
+    :return: Tuple of list of cursor offsets at end of each timestep, and list of configurations with time from
+        `TIMESTEP`, box edges from orthogonal `BOX BOUNDS`, an empty energy array, and nucleotides sorted by atom id
+    """
+    ...
+
+
+def find_configuration_by_time(
+    file_path: str, target_time: int, case_insensitive_headers: bool = False
+) -> tuple[int, tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]:
+    """
+    Read the configuration whose time is nearest to `target_time`, scanning only time headers to find it
+
+    Scanning stops at the first configuration at or after `target_time` if times have been increasing up to it,
+    so a later configuration of a file whose times restart is only found if times decrease before that point.
+    Of configurations equally near, the first one is returned.
+
+    :param file_path: Path to trajectory file
+    :param target_time: Simulation time to look for
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Tuple of file cursor offset of the configuration start, usable as `offset` of other functions,
+        and the configuration, same as in `read_configurations`, raises ValueError if the file has no configuration
+    """
+    ...
+
+
+def read_configurations_from_stdin(limit: int, case_insensitive_headers: bool = False) -> tuple[
+    list[int], list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]
+]:
+    """
+    Read up to number of `limit` configurations from standard input, e.g. piped from a decompression tool
+
+    Standard input is not seekable, reading starts from its current position and offsets count from there.
+    Input is read ahead past the last returned configuration, so call once with a large enough `limit`
+    rather than in chunks.
+
+    :param limit: Read up to this number of configurations, or until end of input
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Tuple of list of offsets at end of each configuration, and list of configurations,
+        same as `read_configurations`
+    """
+    ...
+
+
+def read_configurations_multi(file_paths: list[str], limit: int, case_insensitive_headers: bool = False) -> tuple[
+    list[int], list[int], list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]
+]:
+    """
+    Read up to number of `limit` configurations from several trajectory files as one continuous stream,
+    e.g. checkpointed `traj.0.dat`, `traj.1.dat`, ...
+
+    Configurations never span files, the last configuration of each file ends at its end of file and a
+    truncated one raises ValueError like `read_configurations`. Times are returned as stored, even if they
+    restart in a later file.
+
+    :param file_paths: Paths to trajectory files, read in order
+    :param limit: Read up to this number of configurations across all files
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Tuple of list of source file index in `file_paths` per configuration,
+        list of cursor offsets at end of each configuration within its source file,
+        and list of configurations, same as `read_configurations`
+    """
+    ...
+
+
+def read_configurations_multi_parallel(
+    specs: list[tuple[str, int, int]], case_insensitive_headers: bool = False
+) -> tuple[
+    list[int], list[int], list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]
+]:
+    """
+    Read several trajectory files concurrently, each from its own offset with its own limit,
+    and concatenate configurations in order of `specs`
+
+    :param specs: List of tuples of file path, file cursor offset and limit, same as `read_configurations`
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Tuple of list of spec index per configuration, list of cursor offsets at end of each configuration
+        within its file, and list of configurations, same as `read_configurations_multi`
+    """
+    ...
+
+
+def read_raw_lines(
+    file_path: str, offset: int, limit: int, case_insensitive_headers: bool = False
+) -> tuple[list[int], list[list[str]]]:
+    """
+    Read up to number of `limit` configurations from trajectory as their original text lines, without parsing,
+    so they can be written back byte for byte
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Tuple of list of cursor offsets at end of each configuration, same as `read_configurations`,
+        and list of lines per configuration, each including its line terminator if it has one
+    """
+    ...
+
+
+@typing.overload
+def read_indicies(
+    file_path: str, offset: int, limit: int, case_insensitive_headers: bool = False, require_frame_start: bool = False,
+    return_body_offsets: typing.Literal[False] = False
+) -> list[int]:
+    ...
+
+
+@typing.overload
+def read_indicies(
+    file_path: str, offset: int, limit: int, case_insensitive_headers: bool = False, require_frame_start: bool = False,
+    *, return_body_offsets: typing.Literal[True]
+) -> tuple[list[int], list[int]]:
+    ...
+
+
+@typing.overload
+def read_indicies(
+    file_path: str, offset: int, limit: int, case_insensitive_headers: bool = False, require_frame_start: bool = False,
+    *, return_times: typing.Literal[True]
+) -> tuple[list[int], list[int]]:
+    ...
+
+
+def read_indicies(
+    file_path: str, offset: int, limit: int, case_insensitive_headers: bool = False, require_frame_start: bool = False,
+    return_body_offsets: bool = False, return_times: bool = False
+) -> list[int] | tuple[list[int], list[int]]:
+    """
+    Read cursor offsets at end of each configuration, useful for building trajectory indicies
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :param require_frame_start: Raise ValueError if `offset` is not at a `t =` line, otherwise an `offset`
+        inside a configuration skips forward to the next configuration
+    :param return_body_offsets: Also return the cursor offset of the first nucleotide line of each configuration,
+        after its 3 header lines, or its end offset if it has no nucleotide lines
+    :param return_times: Also return the time of each configuration, e.g. to save an index with `save_index`,
+        raises ValueError together with `return_body_offsets`
+    :return: List of cursor offsets, same as end offsets returned by `read_configurations`,
+        the last one can be passed as `offset` to continue reading, or a tuple of it and the list of body offsets
+        or times
+    """
+    ...
+
+
+def read_indicies_mmap(
+    file_path: str, offset: int, limit: int, case_insensitive_headers: bool = False, require_frame_start: bool = False
+) -> list[int]:
+    """
+    Same as `read_indicies`, memory-mapping the file and scanning for lines starting with `t`,
+    which `read_indicies` also does unless `return_body_offsets` is set or a sidecar index is used
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :param require_frame_start: Raise ValueError if `offset` is not at a `t =` line
+    :return: List of cursor offsets, identical to `read_indicies`
+    """
+    ...
+
+
+def build_index(file_path: str, case_insensitive_headers: bool = False) -> int:
+    """
+    Scan a trajectory once and save start offset, end offset and time of each configuration to a binary sidecar
+    `<file_path>.fidx`, along with the file's size and modification time. `read_indicies`, `read_frames` and
+    `find_configuration_by_time` then take offsets and times from it instead of scanning the file, as long as the
+    file is unchanged and they use the same `case_insensitive_headers`, otherwise they scan as before
+
+    :param file_path: Path to trajectory file
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Number of configurations indexed
+    """
+    ...
+
+
+def save_index(
+    file_path: str, end_offsets: list[int], times: list[int], case_insensitive_headers: bool = False
+) -> None:
+    """
+    Save end offsets and times of every configuration, collected while reading the whole trajectory, to the
+    sidecar `<file_path>.fidx` that `build_index` writes, without scanning the file again
+
+    :param file_path: Path to trajectory file
+    :param end_offsets: Cursor offsets at end of each configuration from the start of the file,
+        as returned by `read_indicies(file_path, 0, n)`
+    :param times: Time of each configuration
+    :param case_insensitive_headers: Whether `end_offsets` were read with `case_insensitive_headers`
+    :return: None, raises ValueError if `times` and `end_offsets` differ in length or the last end offset is not
+        the file size, as an index of part of the file would be loaded as the whole file
+    """
+    ...
+
+
+def load_index(file_path: str, case_insensitive_headers: bool = False) -> list[int] | None:
+    """
+    Read cursor offsets at end of each configuration from the sidecar `<file_path>.fidx` written by `build_index`
+
+    :param file_path: Path to trajectory file
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: List of cursor offsets, identical to `read_indicies(file_path, 0, n)` for the whole file, None if
+        there is no index, it is unreadable, or the file or `case_insensitive_headers` changed since it was built
+    """
+    ...
+
+
+def truncate_incomplete(
+    file_path: str, dry_run: bool = False, case_insensitive_headers: bool = False
+) -> int | None:
+    """
+    Cut a partial last configuration, left by a simulation killed while writing, off the end of a trajectory.
+    The last configuration is partial if it has fewer lines than the one before it, e.g. missing nucleotides or
+    headers, or does not parse because a number is cut off in its last line
+
+    :param file_path: Path to an uncompressed trajectory file
+    :param dry_run: Only find the partial configuration, leaving the file unchanged
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Offset the file was, or with `dry_run` would be, truncated at, None if the last configuration is complete
+    """
+    ...
+
+
+def set_num_threads(n: int) -> None:
+    """
+    Set the number of worker threads used to parse and format configurations in parallel, for all later calls.
+    By default there is one per core, which can be too many on shared machines such as HPC login nodes
+
+    :param n: Number of worker threads, 0 to go back to one per core
+    """
+    ...
+
+
+def get_num_threads() -> int:
+    """
+    :return: Number of worker threads used to parse and format configurations in parallel
+    """
+    ...
+
+
+def time_deltas(file_path: str, offset: int, case_insensitive_headers: bool = False) -> npt.NDArray[np.int64]:
+    """
+    Differences between consecutive configuration times `t[i+1] - t[i]`, scanning only time headers
+    from file cursor `offset` to end of file, useful to spot irregular output cadence
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Array of time differences, one shorter than number of configurations, raises ValueError if a
+        difference does not fit int64
+    """
+    ...
+
+
+def read_boxes(
+    file_path: str, offset: int, limit: int, case_insensitive_headers: bool = False
+) -> tuple[npt.NDArray[np.uint64], npt.NDArray[np.float64]]:
+    """
+    Box dimensions over time, parsing only time and box headers and skipping nucleotide lines unparsed,
+    for following the box of NPT simulations much cheaper than full reads
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Maximum number of configurations to read
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Tuple of times of shape (n_frames,) and box dimensions of shape (n_frames, 3)
+    """
+    ...
+
+
+def read_headers(
+    file_path: str, offset: int, limit: int, case_insensitive_headers: bool = False
+) -> tuple[npt.NDArray[np.uint64], npt.NDArray[np.float64], npt.NDArray[np.float64]]:
+    """
+    Times, boxes and energies of configurations, parsing only the header lines and skipping nucleotide lines
+    unparsed, e.g. to plot energy over time without reading whole configurations
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Maximum number of configurations to read
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Tuple of times of shape (n_frames,), box dimensions of shape (n_frames, 3) and energies of shape
+        (n_frames, energy_count), raises ValueError if configurations have different numbers of energy values
+    """
+    ...
+
+
+def read_topology(file_path: str) -> tuple[
+    npt.NDArray[np.int64], list[str], npt.NDArray[np.int64], list[bool]
+]:
+    """
+    Parse oxDNA topology file, detecting the format from the header. Classic files have a
+    `n_nucleotides n_strands` header and a `strand_id base 3'_neighbor 5'_neighbor` line per nucleotide,
+    oxDNA 3 files have a `n_nucleotides n_strands 5->3` header and a line per strand with its sequence 5' to 3'
+    followed by attributes such as `circular=true`, numbering strands from 1
+
+    :param file_path: Path to topology file
+    :return: Tuple of strand id of each nucleotide of shape (n_nucleotides,), base of each nucleotide,
+        3' and 5' neighbor indicies of shape (n_nucleotides, 2) with -1 for strand ends,
+        and whether each strand is circular in increasing order of strand id,
+        raises ValueError if the counts in the header do not match the nucleotide lines
+    """
+    ...
+
+
+def validate_trajectory(
+    file_path: str,
+    offset: int,
+    limit: int,
+    case_insensitive_headers: bool = False,
+    topology_path: str | None = None,
+) -> ValidationSummary:
+    """
+    Scan configurations and report problems without raising on them, to triage a file before a long analysis.
+    Headers are parsed while nucleotide lines are only counted, so this is much faster than a full read
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Scan up to this number of configurations, or until end of file
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :param topology_path: Path to the topology of the trajectory, every configuration must then have as many
+        nucleotides as it, to catch a topology and trajectory that do not belong together
+    :return: Summary of the scanned configurations, raises IOError only if the file cannot be read
+    """
+    ...
+
+
+def verify_checksums(
+    file_path: str, offset: int, limit: int, expected: list[int], case_insensitive_headers: bool = False
+) -> list[int]:
+    """
+    Compare CRC32 checksums of up to `limit` configurations from file cursor `offset` with a manifest,
+    without parsing values
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param expected: Checksum of each configuration, `zlib.crc32` of its bytes from its start
+        to the start of the next configuration or end of file
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: Indices of configurations counted from `offset` whose checksum differs,
+        raises ValueError if the number of configurations read differs from the length of `expected`
+    """
+    ...
+
+
+@typing.overload
+def read_energies(
+    file_path: str, offset: int, limit: int, case_insensitive_headers: bool = False,
+    raw_energy: typing.Literal[False] = False
+) -> list[npt.NDArray[np.float64]]:
+    ...
+
+
+@typing.overload
+def read_energies(
+    file_path: str, offset: int, limit: int, case_insensitive_headers: bool = False, *,
+    raw_energy: typing.Literal[True]
+) -> list[str]:
+    ...
+
+
+def read_energies(
+    file_path: str, offset: int, limit: int, case_insensitive_headers: bool = False, raw_energy: bool = False
+) -> list[npt.NDArray[np.float64]] | list[str]:
+    """
+    Read energy headers of up to `limit` configurations, scanning only headers, from file cursor `offset`
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :param raw_energy: Return the text after `E =` of each configuration as written, without surrounding
+        whitespace, instead of parsing it, so formatting such as `-2` vs `-2.0` is kept
+    :return: List of energy arrays, as many values as each frame's energy line has, or list of energy strings
+    """
+    ...
+
+
+def centers_of_mass(file_path: str, offset: int, limit: int, masses: list[float] | None = None) -> tuple[
+    npt.NDArray[np.uint64], npt.NDArray[np.float64]
+]:
+    """
+    Compute center of mass of nucleotide positions per configuration, without unwrapping periodic boundaries
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param masses: Per-nucleotide masses, uniform if not given, raises ValueError if length does not match
+        the number of nucleotides of a configuration
+    :return: Tuple of times and Nx3 centers of mass
+    """
+    ...
+
+
+def temperature(file_path: str, offset: int, limit: int, masses: list[float] | None = None) -> tuple[
+    npt.NDArray[np.uint64], npt.NDArray[np.float64]
+]:
+    """
+    Compute temperature in oxDNA units per configuration from velocities and angular velocities,
+    by equipartition over 6 degrees of freedom per nucleotide with unit moment of inertia.
+    Raises ValueError for nucleotide lines without the 6 velocity columns
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param masses: Per-nucleotide masses weighting translational kinetic energy, 1.0 if not given,
+        raises ValueError if length does not match the number of nucleotides of a configuration
+    :return: Tuple of times and temperatures
+    """
+    ...
+
+
+def strand_centers(file_path: str, top_or_lengths: str | list[int], offset: int, limit: int) -> npt.NDArray[np.float64]:
+    """
+    Compute centroid of nucleotide positions of each strand per configuration, without unwrapping periodic boundaries
+
+    :param file_path: Path to trajectory file
+    :param top_or_lengths: Path to topology file in classic or oxDNA 3 format, or number of nucleotides of each
+        strand in file order
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :return: Array of shape (configurations, strands, 3), raises ValueError if the number of nucleotides
+        of a configuration differs from the total strand length
+    """
+    ...
+
+
+def read_rmsd(file_path: str, offset: int, limit: int, reference: npt.NDArray[np.float64]) -> tuple[
+    npt.NDArray[np.uint64], npt.NDArray[np.float64]
+]:
+    """
+    Compute root-mean-square deviation of nucleotide positions from reference positions per configuration,
+    without alignment or unwrapping periodic boundaries
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param reference: Reference positions as the first 3 columns of an array with one row per nucleotide,
+        e.g. nucleotide vectors of a configuration, raises ValueError if a configuration has a different
+        number of nucleotides
+    :return: Tuple of times and RMSD
+    """
+    ...
+
+
+def end_to_end_vectors(file_path: str, offset: int, limit: int, first: int, last: int) -> tuple[
+    npt.NDArray[np.uint64], npt.NDArray[np.float64]
+]:
+    """
+    Compute unit vector from nucleotide `first` to nucleotide `last` per configuration, using the nearest
+    periodic image of `last`, e.g. for end-to-end vector autocorrelation
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param first: Index of the start nucleotide
+    :param last: Index of the end nucleotide
+    :return: Tuple of times and Nx3 unit vectors, raises ValueError if a configuration has no nucleotide `first`
+        or `last`, both positions coincide, or a box edge is zero, negative or not finite
+    """
+    ...
+
+
+def read_position_statistics(file_path: str, offset: int, limit: int) -> tuple[
+    npt.NDArray[np.float64], npt.NDArray[np.float64]
 ]:
     """
-    Read up to number of `limit` configurations from trajectory, starting from file cursor `offset`
+    Compute time-averaged position of each nucleotide and its per-component variance in a single pass,
+    without holding configurations in memory
 
     :param file_path: Path to trajectory file
     :param offset: Start reading from this file cursor offset
     :param limit: Read up to this number of configurations, or until end of file
-    :return: Tuple of list of cursor offsets at end of each configuration, and list of configurations,
-        each a tuple of
-        - Time
-        - Box dimensions
-        - Energy
-        - Nucleotide vectors
+    :return: Tuple of Nx3 mean positions and Nx3 population variances, dividing by the number of configurations,
+        raises ValueError if configurations have different numbers of nucleotides
     """
     ...
 
 
-def read_indicies(file_path: str, offset: int, limit: int) -> list[int]:
+def read_displacements(file_path: str, offset: int, limit: int, minimum_image: bool = False) -> npt.NDArray[np.float64]:
     """
-    Read cursor offsets at end of each configuration, useful for building trajectory indicies
+    Compute distance each nucleotide moved between consecutive configurations, e.g. to find the most mobile ones
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param minimum_image: Use the nearest periodic image of the new position with the later configuration's box,
+        so crossing a box boundary is not counted as a jump across the box
+    :return: Array of shape `(n_configurations - 1, n_nucleotides)`, raises ValueError if consecutive configurations
+        have different numbers of nucleotides
+    """
+    ...
+
+
+def read_pair_distances(
+    file_path: str, offset: int, limit: int, pairs: list[tuple[int, int]]
+) -> npt.NDArray[np.float64]:
+    """
+    Compute distance between the nearest periodic images of nucleotide pairs per configuration,
+    e.g. to follow base pairs or contacts, parsing only nucleotides in `pairs`
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param pairs: Pairs of nucleotide indicies
+    :return: Array of shape `(n_configurations, len(pairs))`, raises ValueError if an index is out of range
+        for a configuration
+    """
+    ...
+
+
+def clash_count(file_path: str, offset: int, limit: int, radius: float) -> tuple[
+    npt.NDArray[np.uint64], npt.NDArray[np.uint64]
+]:
+    """
+    Count nucleotide pairs closer than `radius` per configuration, using the nearest periodic image,
+    e.g. to find overlapping nucleotides in a design
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param radius: Clash distance, raises ValueError if not positive
+    :return: Tuple of times and number of clashing pairs, each pair counted once
+    """
+    ...
+
+
+def read_position_bounds(file_path: str, offset: int, limit: int) -> tuple[
+    npt.NDArray[np.uint64], npt.NDArray[np.float64]
+]:
+    """
+    Compute bounding box of nucleotide positions per configuration, without unwrapping periodic boundaries
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :return: Tuple of times and Nx2x3 bounds, minimum position in row 0 and maximum in row 1,
+        inf and -inf for configurations without nucleotides
+    """
+    ...
+
+
+def oriented_bbox(file_path: str, offset: int, limit: int, return_rotations: bool = False) -> (
+    npt.NDArray[np.float64] | tuple[npt.NDArray[np.float64], npt.NDArray[np.float64]]
+):
+    """
+    Compute bounding box of nucleotide positions along principal axes of their covariance per configuration,
+    without unwrapping periodic boundaries
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param return_rotations: Also return rotation matrices
+    :return: Nx3 extents along principal axes, largest spread first, or if `return_rotations`
+        a tuple of extents and Nx3x3 rotation matrices with principal axes as rows
+    """
+    ...
+
+
+def p2_order(
+    file_path: str, offset: int, limit: int, axis: tuple[float, float, float]
+) -> npt.NDArray[np.float64]:
+    """
+    Compute nematic order of a1 vectors relative to a fixed direction per configuration,
+    mean of P2(cos θ) = (3 cos² θ - 1) / 2 over nucleotides, where θ is the angle between a1 and `axis`
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param axis: Reference direction, normalized internally, raises ValueError if zero
+    :return: Array of order per configuration, 1 when all a1 are parallel or antiparallel to `axis`,
+        -0.5 when all are perpendicular, and NaN for configurations without nucleotides
+    """
+    ...
+
+
+def equilibration_frame(file_path: str, offset: int, limit: int, window: int, tol: float) -> int | None:
+    """
+    Find the configuration after which temperature has equilibrated, computing temperature like `temperature`
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param window: Number of configurations of the moving average, raises ValueError if 0
+    :param tol: Absolute tolerance of the moving average from the mean temperature over all configurations
+    :return: Index counted from `offset` of the first configuration from which every moving average stays
+        within `tol` of the mean, or None if there are fewer than `window` configurations or the last
+        moving average is outside `tol`
+    """
+    ...
+
+
+def check_energy_sum(file_path: str, offset: int, limit: int, tol: float) -> list[int]:
+    """
+    Find configurations whose total energy differs from the sum of potential and kinetic energy by more than `tol`,
+    reading only headers
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :param tol: Absolute tolerance
+    :return: List of configuration indices counted from `offset`, raises ValueError if an energy line
+        does not have exactly 3 values, total, potential and kinetic
+    """
+    ...
+
+
+def find_out_of_box(file_path: str, offset: int, limit: int) -> list[tuple[int, int]]:
+    """
+    Find configurations with a nucleotide position outside of the box `[0, b)` along any axis
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :return: List of tuples of configuration index counted from `offset`, and first offending nucleotide index
+    """
+    ...
+
+
+def orientation_norm_range(file_path: str, offset: int, limit: int) -> tuple[float, float, float, float]:
+    """
+    Find range of |a1| and |a3| over all nucleotides and configurations, values far from 1 indicate corruption
 
     :param file_path: Path to trajectory file
     :param offset: Start reading from this file cursor offset
     :param limit: Read up to this number of configurations, or until end of file
-    :return: List of cursor offsets
+    :return: Tuple of min |a1|, max |a1|, min |a3|, max |a3|, (inf, -inf, inf, -inf) if there are no nucleotides
+    """
+    ...
+
+
+def count_mode(file_path: str, offset: int) -> tuple[int, list[tuple[int, int]]]:
+    """
+    Find the most common nucleotide count over configurations from file cursor `offset` to end of file,
+    counting lines without parsing them
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :return: Tuple of most common nucleotide count, the first seen one on ties or 0 if there are no configurations,
+        and list of tuples of configuration index counted from `offset` and nucleotide count for configurations
+        with a different count
     """
     ...
 
 
 def dumps_configurations(
-    configurations: list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]
+    configurations: list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]],
+    permutation: list[int] | None = None,
+    pad_to: int | None = None,
+    wrap: bool = False
 ) -> list[str]:
     """
     Serialize configurations to strings using trajectory file format
@@ -45,6 +979,382 @@ def dumps_configurations(
         - box: np.array
         - energy: np.array
         - nucleotides: np.array
+    :param permutation: Row order of nucleotides to write, row `i` is `nucleotides[permutation[i]]`;
+        must contain every row index exactly once
+    :param pad_to: Right-pad each nucleotide row with zeros to this many values, e.g. 15 to write zero velocities
+        for 9 column configurations, raises ValueError if less than the number of columns
+    :param wrap: Fold positions into the primary box `[0, box)` with `pos - box * floor(pos / box)` using each
+        configuration's box, other columns are written unchanged
+    :return: List of string per configuration
+    """
+    ...
+
+
+def write_configurations(
+    file_path: str,
+    configs: list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]],
+    append: bool = False
+) -> list[int]:
+    """
+    Write configurations to a file using trajectory file format, serializing them in parallel and writing them
+    in order, without holding the text of all configurations at once
+
+    :param file_path: Path to output trajectory file
+    :param configs: List of tuples of
+        - time: int
+        - box: np.array
+        - energy: np.array
+        - nucleotides: np.array
+    :param append: Append to the file instead of overwriting it, adding a newline first if the file does not
+        end with one
+    :return: End offset of each written configuration, i.e. file cursor offset of the next configuration
+    """
+    ...
+
+
+def dumps_configurations_positions(
+    configurations: list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]
+) -> list[str]:
+    """
+    Serialize configurations to strings using trajectory file format with only the 3 position columns
+    per nucleotide, skipping formatting of the other columns
+
+    :param configurations: List of tuples of
+        - time: int
+        - box: np.array
+        - energy: np.array
+        - nucleotides: np.array, at least 3 columns
     :return: List of string per configuration
     """
     ...
+
+
+def dumps_configurations_xyz(
+    configurations: list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]],
+    element: str,
+    bases: list[str] | None = None
+) -> list[str]:
+    """
+    Serialize nucleotide positions to strings using XYZ file format, the same records `write_configurations_xyz`
+    writes when given `bases`
+
+    :param configurations: List of tuples of
+        - time: int
+        - box: np.array
+        - energy: np.array
+        - nucleotides: np.array, at least 3 columns
+    :param element: Element label written for every nucleotide, e.g. "C"
+    :param bases: Label each nucleotide with its base instead, one per nucleotide
+    :return: List of string per configuration, with nucleotide count, time comment and one line per nucleotide,
+        raises ValueError if `bases` has a different length than a configuration
+    """
+    ...
+
+
+def dumps_configurations_lammps(
+    configurations: list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]],
+    box_origin: tuple[float, float, float] = (0.0, 0.0, 0.0)
+) -> list[str]:
+    """
+    Serialize nucleotide positions to strings using LAMMPS dump file format
+
+    :param configurations: List of tuples of
+        - time: int
+        - box: np.array, 3 values
+        - energy: np.array
+        - nucleotides: np.array, at least 3 columns
+    :param box_origin: Lower box bounds, upper bounds are `box_origin + box`
+    :return: List of string per configuration, with `TIMESTEP`, `NUMBER OF ATOMS`, periodic `BOX BOUNDS` and
+        `ATOMS id x y z` sections, ids starting from 1 in nucleotide order
+    """
+    ...
+
+
+def write_configurations_pdb(
+    file_path: str,
+    configurations: list[tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]],
+    strands: list[tuple[int, int, str]],
+    sites_per_nucleotide: int
+) -> None:
+    """
+    Write site positions to a PDB file, converted to angstrom, with a CRYST1 record from the first box,
+    one MODEL per configuration, one chain per strand and one residue per nucleotide named after its base,
+    then CONECT records bonding the sites of each nucleotide and the first sites of neighboring nucleotides
+
+    :param file_path: Path to output PDB file
+    :param configurations: List of tuples of
+        - time: int, written as a REMARK before each model
+        - box: np.array, 3 values
+        - energy: np.array, unused
+        - sites: np.array, `sites_per_nucleotide` rows of 3 columns per nucleotide
+    :param strands: First nucleotide, last nucleotide and sequence of each strand, in nucleotide order
+    :param sites_per_nucleotide: 1 for a pseudo-atom `C` per nucleotide, 2 for backbone `P` and base `N` atoms
+    """
+    ...
+
+
+def write_configuration_lammps_data(
+    file_path: str,
+    configuration: tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]],
+    strands: list[tuple[int, int, str]]
+) -> None:
+    """
+    Write a configuration as a LAMMPS data file for `atom_style hybrid bond ellipsoid`, to start a run of the
+    LAMMPS oxdna package. Each nucleotide is an ellipsoid atom of type 1 to 4 for A, C, G and T or U, in the
+    molecule of its strand, with orientation as a quaternion from a1 and a3, and bonded to the next nucleotide
+    of its strand. Positions are wrapped into a box from the origin with image flags, and a Velocities section
+    is written if nucleotides have 15 columns
+
+    :param file_path: Path to output data file
+    :param configuration: Tuple of
+        - time: int
+        - box: np.array, 3 values
+        - energy: np.array, unused
+        - nucleotides: np.array, at least 9 columns
+    :param strands: First nucleotide, last nucleotide and sequence of each strand, in nucleotide order
+    """
+    ...
+
+
+class ValidationSummary:
+    """
+    Result of `validate_trajectory`
+    """
+    frames: int
+    """
+    Number of configurations scanned, including those with problems
+    """
+    nucleotide_count: int | None
+    """
+    Number of nucleotides of the topology if given, else of the first configuration with valid headers,
+    None if there is none
+    """
+    first_time: int | None
+    last_time: int | None
+    problems: list[tuple[int, str]]
+    """
+    Index relative to `offset` and message of each configuration with invalid headers,
+    a nucleotide count different from `nucleotide_count`, or a time not after that of the configuration before it
+    """
+
+
+class FrameView:
+    """
+    Lazy view of a single configuration at file cursor `offset`, parsed on first property access and cached
+
+    :param file_path: Path to trajectory file
+    :param offset: File cursor offset of the configuration start
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    """
+    file_path: str
+    offset: int
+
+    def __init__(self, file_path: str, offset: int, case_insensitive_headers: bool = False) -> None:
+        ...
+
+    @property
+    def is_parsed(self) -> bool:
+        """Whether the configuration has been parsed"""
+        ...
+
+    @property
+    def time(self) -> int:
+        ...
+
+    @property
+    def box(self) -> npt.NDArray[np.float64]:
+        ...
+
+    @property
+    def positions(self) -> npt.NDArray[np.float64]:
+        """Nx3 nucleotide mass centers"""
+        ...
+
+
+class TrajectoryReader:
+    """
+    Iterator over configurations of a trajectory, starting from file cursor `offset`, parsing one at a time
+    as they are requested, each a tuple of time, box dimensions, energy and nucleotide vectors
+    like `read_configurations`
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :param reuse_buffer: Write nucleotide vectors of each configuration into the same array as the previous
+        configuration instead of a new one, when it has the same shape and is still writeable. The array returned
+        for a configuration is overwritten by the next one, copy it to keep the values
+    """
+
+    def __init__(
+        self, file_path: str, offset: int = 0, case_insensitive_headers: bool = False, reuse_buffer: bool = False
+    ) -> None:
+        ...
+
+    def __iter__(self) -> TrajectoryReader:
+        ...
+
+    def __next__(self) -> tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]:
+        ...
+
+    def enumerate_with_offsets(self) -> Iterator[
+        tuple[int, int, tuple[int, npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]]]
+    ]:
+        """
+        Iterate over remaining configurations of this reader, advancing it
+
+        :return: Iterator of tuples of configuration index counted from `offset`,
+            file cursor offset of the configuration start, and configuration
+        """
+        ...
+
+
+class TrajectoryWriter:
+    """
+    Writer streaming configurations to a trajectory file one at a time, e.g. from a running analysis.
+    Use as a context manager or call `close`, writes are buffered until then or `flush`
+
+    :param file_path: Path to output trajectory file
+    :param append: Append to the file instead of overwriting it, adding a newline first if the file does not
+        end with one
+    """
+    offset: int
+    """
+    File cursor offset where the next configuration will be written
+    """
+    closed: bool
+
+    def __init__(self, file_path: str, append: bool = False) -> None:
+        ...
+
+    def write(
+        self, time: int, box: npt.NDArray[np.float64], energy: npt.NDArray[np.float64],
+        nucleotides: npt.NDArray[np.float64]
+    ) -> int:
+        """
+        Write a configuration using trajectory file format
+
+        :return: End offset of the written configuration, raises ValueError if the writer is closed
+        """
+        ...
+
+    def flush(self) -> None:
+        ...
+
+    def close(self) -> None:
+        ...
+
+    def __enter__(self) -> TrajectoryWriter:
+        ...
+
+    def __exit__(self, exc_type: typing.Any, exc_value: typing.Any, traceback: typing.Any) -> None:
+        ...
+
+
+def iter_configurations(file_path: str, offset: int = 0, case_insensitive_headers: bool = False) -> TrajectoryReader:
+    """
+    Iterate over configurations from file cursor `offset` to end of file, parsing exactly one per step,
+    so memory does not depend on the number of configurations, e.g. `for conf in iter_configurations(path)`
+
+    :param file_path: Path to trajectory file
+    :param offset: Start reading from this file cursor offset
+    :param case_insensitive_headers: Match header keys such as `T =` and `B =` regardless of case, otherwise they raise ValueError
+    :return: `TrajectoryReader` yielding configurations like `read_configurations`
+    """
+    ...
+
+
+def append_configuration(
+    file_path: str, time: int, box: npt.NDArray[np.float64], energy: npt.NDArray[np.float64],
+    nucleotides: npt.NDArray[np.float64]
+) -> int:
+    """
+    Append a configuration to trajectory file using trajectory file format, creating the file if missing
+
+    :param file_path: Path to trajectory file
+    :param time: Time
+    :param box: Box dimensions
+    :param energy: Energy
+    :param nucleotides: Nucleotide vectors
+    :return: File size after appending, which is the end offset of the appended configuration
+    """
+    ...
+
+
+def downsample_trajectory(input_path: str, output_path: str, stride: int) -> int:
+    """
+    Write every `stride`-th configuration of a trajectory to a new file, starting from the first one,
+    copying bytes verbatim so values keep their original text
+
+    :param input_path: Path to trajectory file to read
+    :param output_path: Path to trajectory file to write, overwritten if it exists
+    :param stride: Keep one configuration out of every `stride`, raises ValueError if 0
+    :return: Number of configurations written
+    """
+    ...
+
+
+def merge_trajectories_by_time(path_a: str, path_b: str, output_path: str) -> int:
+    """
+    Write configurations of two trajectories to a new file sorted by time, copying bytes verbatim
+    so values keep their original text
+
+    Configurations with equal times are written in the order of their files, `path_a` first,
+    and configurations of one file with equal times keep their order in that file.
+
+    :param path_a: Path to first trajectory file
+    :param path_b: Path to second trajectory file
+    :param output_path: Path to trajectory file to write, overwritten if it exists
+    :return: Number of configurations written
+    """
+    ...
+
+
+def write_positions_csv(in_path: str, out_path: str) -> int:
+    """
+    Write nucleotide positions of every configuration to a CSV file with header `frame,time,nucleotide,x,y,z`
+    and one row per nucleotide per configuration
+
+    :param in_path: Path to trajectory file to read
+    :param out_path: Path to CSV file to write, overwritten if it exists
+    :return: Number of configurations written
+    """
+    ...
+
+
+def write_configurations_xyz(
+    in_path: str, out_path: str, topology_path: str, offset: int, limit: int, strand_centers: bool = False
+) -> int:
+    """
+    Write configurations to a multi-frame XYZ file for VMD or OVITO, each frame with a record count line,
+    a `t = <time>` comment line, and one `label x y z` record per nucleotide labelled with its base, as
+    `dumps_configurations_xyz` serializes them
+
+    :param in_path: Path to trajectory file to read
+    :param out_path: Path to XYZ file to write, overwritten if it exists
+    :param topology_path: Path to topology file of the trajectory
+    :param offset: Start reading from this file cursor offset
+    :param limit: Write up to this number of configurations, or until end of file
+    :param strand_centers: Write one record per strand instead, at the centroid of its nucleotide positions
+        without unwrapping periodic boundaries, labelled `S` and its strand id
+    :return: Number of configurations written, raises ValueError if a configuration has a different number
+        of nucleotides than the topology
+    """
+    ...
+
+
+def read_configurations_mmap_out(
+    in_path: str, out_path: str, offset: int, limit: int
+) -> tuple[npt.NDArray[np.uint64], npt.NDArray[np.float64]]:
+    """
+    Read up to number of `limit` configurations from trajectory, starting from file cursor `offset`, and write
+    nucleotide vectors to a raw float64 file, so trajectories larger than memory can be used as one array
+
+    :param in_path: Path to trajectory file
+    :param out_path: Path to output file, overwritten if it exists, in native byte order without header
+    :param offset: Start reading from this file cursor offset
+    :param limit: Read up to this number of configurations, or until end of file
+    :return: Tuple of times and `np.memmap` of `out_path` with shape (configurations, nucleotides, columns),
+        columns being 15 or 9 as detected from the first nucleotide, an in-memory empty array if nothing was
+        written, raises ValueError if nucleotide or column counts differ
+    """
+    ...
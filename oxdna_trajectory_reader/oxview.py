@@ -0,0 +1,93 @@
+from __future__ import annotations
+import datetime
+import json
+
+import numpy as np
+
+from .configuration import Configuration
+from .topology import Strand, Topology
+
+
+def to_oxview(configuration: Configuration, topology: Topology, out_path: str):
+    """
+    Write a configuration and its topology as an oxView scene, one system with a strand per topology strand
+    and a monomer per nucleotide, with monomer ids being nucleotide indices
+
+    :param configuration: `Configuration` to write
+    :param topology: `Topology` of the configuration
+    :param out_path: Path to output `.oxview` file
+    """
+    if len(configuration) != topology.n_monomer:
+        raise ValueError(f"Configuration at time {configuration.time} has {len(configuration)} nucleotides, "
+                         f"topology has {topology.n_monomer}")
+    monomer_class = 'RNA' if configuration.backbone_type == 'RNA' else 'DNA'
+    strands = []
+    for strand_id, strand in topology.strands.items():
+        # nucleotides of a strand are listed 3' to 5' as in the topology
+        monomers = [
+            {
+                'id': index,
+                'type': base,
+                'class': monomer_class,
+                'p': configuration.positions[index].tolist(),
+                'a1': configuration.a1s[index].tolist(),
+                'a3': configuration.a3s[index].tolist(),
+                'n3': index - 1 if index > strand.start else -1,
+                'n5': index + 1 if index < strand.end else -1,
+            }
+            for index, base in zip(range(strand.start, strand.end + 1), strand.sequence)
+        ]
+        strands.append({
+            'id': strand_id,
+            'end3': strand.start,
+            'end5': strand.end,
+            'class': 'NucleicAcidStrand',
+            'monomers': monomers,
+        })
+    scene = {
+        'date': datetime.datetime.now().isoformat(),
+        'box': np.asarray(configuration.box).tolist(),
+        'systems': [{'id': 0, 'strands': strands}],
+        'forces': [],
+        'selections': [],
+    }
+    with open(out_path, 'w') as f:
+        json.dump(scene, f)
+
+
+def read_oxview(file_path: str) -> tuple[Configuration, Topology]:
+    """
+    Read an oxView scene as a configuration and its topology. Strands of all systems are numbered from 1 in
+    file order and nucleotides renumbered 3' to 5' along each strand, following `n5` from `end3`.
+    Scenes hold no time, energy or velocities, so time is 0 and the rest zeros
+
+    :param file_path: Path to `.oxview` or JSON file
+    :return: Tuple of `Configuration` with 15 columns per nucleotide, and `Topology`
+    """
+    with open(file_path, 'r') as f:
+        scene = json.load(f)
+    rows = []
+    strands: dict[int, Strand] = {}
+    backbone_type = 'oxDNA2'
+    for system in scene['systems']:
+        for strand in system['strands']:
+            monomers = {monomer['id']: monomer for monomer in strand['monomers']}
+            ordered = []
+            monomer_id = strand.get('end3', -1)
+            while monomer_id in monomers and len(ordered) < len(monomers):
+                ordered.append(monomers[monomer_id])
+                monomer_id = monomers[monomer_id].get('n5', -1)
+            # strands without usable neighbor links keep the order they are listed in
+            if len(ordered) != len(monomers):
+                ordered = strand['monomers']
+            start = len(rows)
+            for monomer in ordered:
+                if monomer.get('class') == 'RNA':
+                    backbone_type = 'RNA'
+                rows.append(list(monomer['p']) + list(monomer['a1']) + list(monomer['a3']) + [0.0] * 6)
+            strands[len(strands) + 1] = Strand(start=start, end=len(rows) - 1,
+                                               sequence=''.join(monomer['type'] for monomer in ordered))
+    nucleotides = np.array(rows, dtype=np.float64).reshape(-1, 15)
+    configuration = Configuration(0, np.array(scene['box'], dtype=np.float64), np.zeros(3), nucleotides,
+                                  backbone_type)
+    return configuration, Topology.from_strands(strands)
@@ -0,0 +1,63 @@
+from __future__ import annotations
+
+from .configuration import Configuration, ConfigurationSlice
+from .topology import Strand, Topology
+
+
+class StrandSlice(ConfigurationSlice):
+    """
+    Nucleotides of one strand in a configuration
+
+    Provides `strand` and `sequence` on top of the `ConfigurationSlice` attributes
+    """
+    def __init__(self, conf: Configuration, strand: Strand):
+        super().__init__(conf, strand.start, strand.end + 1, 1)
+        self.strand = strand
+
+    @property
+    def sequence(self) -> str:
+        return self.strand.sequence
+
+    def __repr__(self):
+        return f"<StrandSlice time={self.time} start={self.strand.start} end={self.strand.end}>"
+
+
+class System:
+    """
+    A configuration bound to the topology of its nucleotides
+
+    :param topology: `Topology` of the configuration
+    :param configuration: `Configuration` with as many nucleotides as the topology
+
+    Use `strands` for a view of each strand in topology order, e.g. `system.strands[0].positions`,
+    and `sequence` for the sequence of all nucleotides
+    """
+    def __init__(self, topology: Topology, configuration: Configuration):
+        if len(configuration) != topology.n_monomer:
+            raise ValueError(f"Configuration at time {configuration.time} has {len(configuration)} nucleotides, "
+                             f"topology has {topology.n_monomer}")
+        self.topology = topology
+        self.configuration = configuration
+        self.strands = [StrandSlice(configuration, strand) for strand in topology]
+
+    @property
+    def sequence(self) -> str:
+        return ''.join(strand.sequence for strand in sorted(self.topology, key=lambda strand: strand.start))
+
+    @property
+    def time(self):
+        return self.configuration.time
+
+    @property
+    def box(self):
+        return self.configuration.box
+
+    @property
+    def energy(self):
+        return self.configuration.energy
+
+    def __len__(self):
+        return len(self.configuration)
+
+    def __repr__(self):
+        return f"<System time={self.time} strands={len(self.strands)} len={len(self)}>"
@@ -18,6 +18,14 @@ class Topology:
         with open(file_path, 'r') as f:
             self.strands, self.n_monomer = self._parse_topology(f.readlines())
 
+    @classmethod
+    def from_strands(cls, strands: dict[int, Strand]) -> Topology:
+        """Build a topology from strands by strand id, e.g. read from another format"""
+        topology = cls.__new__(cls)
+        topology.strands = strands
+        topology.n_monomer = sum(len(strand) for strand in strands.values())
+        return topology
+
     @staticmethod
     def _parse_topology(lines: list[str]):
         n_monomer, n_strands = map(int, lines[0].split())
@@ -1,12 +1,12 @@
 from __future__ import annotations
 import os
-import json
+import sys
 import itertools
 import contextlib
 import typing
 
 from .configuration import Configuration
-from .oxdna_trajectory_reader import read_configurations, read_indicies
+from .oxdna_trajectory_reader import read_configurations, read_indicies, save_index, load_index, FrameView
 
 
 CHUNK_SIZE = 20
@@ -17,33 +17,22 @@ class TrajFileIdx:
         self.file_path = trajectory.file_path
         self._file_size = trajectory._file_size
         self._chunk_size = trajectory._chunk_size
-        self._end_offsets = self._read_idx()
+        self._end_offsets = load_index(self.file_path) or []
+        # times of configurations in `_end_offsets`, only collected while indexing, as a loaded index is complete
+        self._times = []
 
     @property
     def index_file_path(self):
-        return f"{self.file_path}.idx"
+        return f"{self.file_path}.fidx"
 
     @property
     def _is_partial_indicies(self):
         return not self._end_offsets or self._end_offsets[-1] < self._file_size
 
-    def _read_idx(self) -> list[int]:
-        with contextlib.suppress(Exception):
-            with open(self.index_file_path, "rt", encoding="utf-8") as f:
-                data = json.load(f)
-                end_offsets = [int(offset + length) for offset, length, _ in data]
-                assert all(i == index for i, (_, _, index) in enumerate(data))
-                assert all(s == e for s, e in zip(data[1:], end_offsets[:-1]))
-                assert end_offsets[-1] == self._file_size
-                return end_offsets
-        return []
-
     def _save_idx(self):
-        start_offsets = [0, *self._end_offsets[:-1]]
-        lengths = [end - start for start, end in zip(start_offsets, self._end_offsets)]
-        indicies = list(zip(start_offsets, lengths, range(len(self._end_offsets))))
-        with open(self.index_file_path, "wt", encoding="utf-8") as f:
-            json.dump(indicies, f)
+        # the index is only a cache, so reading goes on if it cannot be written or the file changed meanwhile
+        with contextlib.suppress(OSError, ValueError):
+            save_index(self.file_path, self._end_offsets, self._times)
 
     def _get_start_offset(self, index: int):
         if index == 0:
@@ -53,24 +42,25 @@ class TrajFileIdx:
             raise IndexError
         return offset
 
-    def _update_end_offsets(self, first_index: int, offsets: list[int]):
+    def _update_end_offsets(self, first_index: int, offsets: list[int], times: list[int]):
         assert first_index >= 0
         if first_index > len(self._end_offsets):
             raise IndexError(f'first_index={first_index} is not continuous with current indicies {len(self._end_offsets)}')
         if len(self._end_offsets) >= first_index + len(offsets):
             return
         self._end_offsets = self._end_offsets[:first_index] + offsets
+        self._times = self._times[:first_index] + times
         if self._end_offsets[-1] >= self._file_size:
             if len(self._end_offsets) > 1:  # skip configuration file, save only for trajectory file
                 self._save_idx()
 
     def _analyze_offsets(self, target_start_index: int):
         start_index = len(self._end_offsets)
-        offsets = read_indicies(self.file_path, self._get_start_offset(start_index),
-                                limit=max(self._chunk_size, target_start_index - start_index))
+        offsets, times = read_indicies(self.file_path, self._get_start_offset(start_index),
+                                       limit=max(self._chunk_size, target_start_index - start_index), return_times=True)
         if not offsets:
             raise ValueError(f'failed to build indicies for "{self.file_path}" from index={start_index}-{target_start_index}')
-        self._update_end_offsets(start_index, offsets)
+        self._update_end_offsets(start_index, offsets, times)
 
     def __getitem__(self, index: int):
         if not isinstance(index, int):
@@ -82,8 +72,8 @@ class TrajFileIdx:
         return self._get_start_offset(index)
 
     def ensure_indicies(self):
-        while self._is_partial_indicies:
-            self._analyze_offsets(len(self._end_offsets) + self._chunk_size)
+        if self._is_partial_indicies:
+            self._analyze_offsets(sys.maxsize)
 
     def get_length(self):
         self.ensure_indicies()
@@ -97,14 +87,16 @@ class Trajectory:
     :param file_path: path to trajectory or configuration file
     :param chunk_size: number of configurations to read at a time during iteration
     :param backbone: type of `Configuration.backbone`, 'oxDNA1', 'oxDNA2', or 'RNA'
+    :param dtype: element type of nucleotide arrays, 'float64' or 'float32'
 
     Provides list-like interface for accessing configurations in a trajectory file
     Use index or for-in to access frames inside a trajectory
     """
-    def __init__(self, file_path: str, chunk_size: int = CHUNK_SIZE, backbone_type='oxDNA2'):
+    def __init__(self, file_path: str, chunk_size: int = CHUNK_SIZE, backbone_type='oxDNA2', dtype: str = 'float64'):
         self.file_path = file_path
         self._chunk_size = chunk_size
         self.backbone_type = backbone_type
+        self.dtype = dtype
         self._file_size = os.path.getsize(file_path)
         self._idx = TrajFileIdx(self)
         self._cached_confs = []
@@ -120,8 +112,8 @@ class Trajectory:
     def _load_config(self, index: int, chunk_size: int):
         assert index >= 0
         offset = self._idx[index]
-        offsets, configurations = read_configurations(self.file_path, offset, chunk_size)
-        self._idx._update_end_offsets(index, offsets)
+        offsets, configurations = read_configurations(self.file_path, offset, chunk_size, dtype=self.dtype)
+        self._idx._update_end_offsets(index, offsets, [configuration[0] for configuration in configurations])
         self._cached_confs = [Configuration(time, box, energy, nucleotides, backbone_type=self.backbone_type)
                               for time, box, energy, nucleotides in configurations]
         self._cached_conf_index = index
@@ -140,6 +132,16 @@ class Trajectory:
             return cached
         return self._load_config(index, chunk_size)[0]
 
+    def frame_view(self, index: int) -> FrameView:
+        """
+        Get a lazy view of configuration at `index`, parsed only when `time`, `box` or `positions` is accessed
+        """
+        if index < 0:
+            if index < -self.length:
+                raise IndexError(f'index={index} is out of bounds for trajectory length={self.length}')
+            index += self.length
+        return FrameView(self.file_path, self._idx[index])
+
     def _iter_forward(self, start: int, stop: int | None, step: int):
         assert start >= 0 and step > 0
         chunk_size = self._chunk_size if self._chunk_size > step else 1
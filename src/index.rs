@@ -0,0 +1,280 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Read, Write};
+use std::time::UNIX_EPOCH;
+
+use super::{ConfigReader, Configuration, HeaderPosition, ParseOptions, SaveLines};
+
+// sidecar `<trajectory>.fidx`: magic, then little endian u64 version, case insensitive headers flag,
+// size and modification time in nanoseconds of the indexed file, configuration count,
+// and start offset, end offset and time of each configuration
+const MAGIC: &[u8; 8] = b"OXDNAIDX";
+const VERSION: u64 = 1;
+
+#[derive(Debug, Clone, Copy)]
+pub struct IndexEntry {
+    pub start: u64,
+    pub end: u64,
+    pub time: u64,
+}
+
+pub fn index_path(file_path: &str) -> String {
+    format!("{}.fidx", file_path)
+}
+
+fn file_stamp(file_path: &str) -> Result<[u64; 2], Error> {
+    let metadata = std::fs::metadata(file_path)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64);
+    Ok([metadata.len(), modified])
+}
+
+// scans the whole file, keeping only header lines
+pub fn build_frame_index(
+    file_path: &str,
+    options: &ParseOptions,
+) -> Result<Vec<IndexEntry>, Error> {
+    let stamp = file_stamp(file_path)?;
+    let options = ParseOptions {
+        header_position: HeaderPosition::Leading,
+        end_offset: None,
+        stride: 1,
+        ..*options
+    };
+    let reader = ConfigReader::new(file_path, 0, SaveLines::Headers, &options)?;
+    let entries = reader
+        .map(|result| {
+            let (start, end, lines) = result?;
+            let time = Configuration::parse_time(&lines, &options)?;
+            Ok(IndexEntry { start, end, time })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    write_entries(file_path, stamp, &entries, &options)?;
+    Ok(entries)
+}
+
+// saves entries a caller collected while reading the whole file, without scanning it again
+pub fn save_frame_index(
+    file_path: &str,
+    entries: &[IndexEntry],
+    options: &ParseOptions,
+) -> Result<(), Error> {
+    let stamp = file_stamp(file_path)?;
+    // an index not covering the whole file would be loaded as if it did
+    let mut end = 0;
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.start != end || entry.end < entry.start {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Configuration {} spans offsets {} to {}, expected it to start at {}",
+                    index, entry.start, entry.end, end
+                ),
+            ));
+        }
+        end = entry.end;
+    }
+    if end != stamp[0] {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Configurations end at offset {}, but {} has {} bytes",
+                end, file_path, stamp[0]
+            ),
+        ));
+    }
+    write_entries(file_path, stamp, entries, options)
+}
+
+fn write_entries(
+    file_path: &str,
+    stamp: [u64; 2],
+    entries: &[IndexEntry],
+    options: &ParseOptions,
+) -> Result<(), Error> {
+    let mut output = BufWriter::new(File::create(index_path(file_path))?);
+    output.write_all(MAGIC)?;
+    let header = [
+        VERSION,
+        options.case_insensitive_headers as u64,
+        stamp[0],
+        stamp[1],
+        entries.len() as u64,
+    ];
+    for value in header {
+        output.write_all(&value.to_le_bytes())?;
+    }
+    for entry in entries {
+        for value in [entry.start, entry.end, entry.time] {
+            output.write_all(&value.to_le_bytes())?;
+        }
+    }
+    output.flush()
+}
+
+// None without an index usable with options, i.e. none was built, it was built with other header options,
+// the file changed since, or the index is unreadable
+pub fn load_frame_index(
+    file_path: &str,
+    options: &ParseOptions,
+) -> Result<Option<Vec<IndexEntry>>, Error> {
+    if options.header_position != HeaderPosition::Leading
+        || options.end_offset.is_some()
+        || options.stride != 1
+    {
+        return Ok(None);
+    }
+    let file = match File::open(index_path(file_path)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    match read_entries(&mut BufReader::new(file), file_path, options) {
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+        result => result,
+    }
+}
+
+fn read_u64(input: &mut impl Read) -> Result<u64, Error> {
+    let mut bytes = [0u8; 8];
+    input.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_entries(
+    input: &mut impl Read,
+    file_path: &str,
+    options: &ParseOptions,
+) -> Result<Option<Vec<IndexEntry>>, Error> {
+    let mut magic = [0u8; 8];
+    input.read_exact(&mut magic)?;
+    if &magic != MAGIC || read_u64(input)? != VERSION {
+        return Ok(None);
+    }
+    let case_insensitive_headers = read_u64(input)? != 0;
+    let stamp = [read_u64(input)?, read_u64(input)?];
+    if case_insensitive_headers != options.case_insensitive_headers
+        || stamp != file_stamp(file_path)?
+    {
+        return Ok(None);
+    }
+    let count = read_u64(input)?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        entries.push(IndexEntry {
+            start: read_u64(input)?,
+            end: read_u64(input)?,
+            time: read_u64(input)?,
+        });
+    }
+    Ok(Some(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_confs, read_offsets};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static FILE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    // trajectory in the temporary directory, removed when dropped along with its index
+    struct TempFile(String);
+
+    impl TempFile {
+        fn new(contents: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "oxdna_index_{}_{}",
+                std::process::id(),
+                FILE_COUNT.fetch_add(1, Ordering::Relaxed)
+            ));
+            std::fs::write(&path, contents).unwrap();
+            Self(path.to_string_lossy().into_owned())
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+            let _ = std::fs::remove_file(index_path(&self.0));
+        }
+    }
+
+    const TRAJECTORY: &str = concat!(
+        "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+        "t = 100\nb = 10 10 10\nE = 0 0 0\n1 0 0 1 0 0 0 0 1\n",
+        "t = 200\nb = 10 10 10\nE = 0 0 0\n2 0 0 1 0 0 0 0 1\n",
+    );
+
+    fn columns(entries: &[IndexEntry]) -> [Vec<u64>; 3] {
+        [
+            entries.iter().map(|entry| entry.start).collect(),
+            entries.iter().map(|entry| entry.end).collect(),
+            entries.iter().map(|entry| entry.time).collect(),
+        ]
+    }
+
+    #[test]
+    fn loads_built_index() {
+        let file = TempFile::new(TRAJECTORY);
+        let options = ParseOptions::default();
+        assert!(load_frame_index(&file.0, &options).unwrap().is_none());
+        let ends = read_offsets(&file.0, 0, 10, &options).unwrap();
+        let built = build_frame_index(&file.0, &options).unwrap();
+        let loaded = load_frame_index(&file.0, &options).unwrap().unwrap();
+        for entries in [&built, &loaded] {
+            assert_eq!(
+                columns(entries),
+                [vec![0, ends[0], ends[1]], ends.clone(), vec![0, 100, 200]]
+            );
+        }
+        assert_eq!(
+            read_offsets(&file.0, ends[0], 10, &options).unwrap(),
+            ends[1..]
+        );
+        assert_eq!(read_confs(&file.0, ends[1], 10, &options).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn saves_collected_entries_covering_the_whole_file() {
+        let file = TempFile::new(TRAJECTORY);
+        let options = ParseOptions::default();
+        let ends = read_offsets(&file.0, 0, 10, &options).unwrap();
+        let entries = [0, ends[0], ends[1]]
+            .into_iter()
+            .zip(&ends)
+            .zip([0, 100, 200])
+            .map(|((start, &end), time)| IndexEntry { start, end, time })
+            .collect::<Vec<_>>();
+        assert!(save_frame_index(&file.0, &entries[..2], &options).is_err());
+        assert!(save_frame_index(&file.0, &entries[1..], &options).is_err());
+        assert!(load_frame_index(&file.0, &options).unwrap().is_none());
+        save_frame_index(&file.0, &entries, &options).unwrap();
+        let loaded = load_frame_index(&file.0, &options).unwrap().unwrap();
+        assert_eq!(columns(&loaded), columns(&entries));
+    }
+
+    #[test]
+    fn ignores_index_built_with_other_options_or_before_changes() {
+        let file = TempFile::new(TRAJECTORY);
+        let options = ParseOptions::default();
+        build_frame_index(&file.0, &options).unwrap();
+        let case_insensitive = ParseOptions {
+            case_insensitive_headers: true,
+            ..Default::default()
+        };
+        assert!(load_frame_index(&file.0, &case_insensitive)
+            .unwrap()
+            .is_none());
+        let strided = ParseOptions {
+            stride: 2,
+            ..Default::default()
+        };
+        assert!(load_frame_index(&file.0, &strided).unwrap().is_none());
+        std::fs::write(&file.0, &TRAJECTORY[..TRAJECTORY.len() - 1]).unwrap();
+        assert!(load_frame_index(&file.0, &options).unwrap().is_none());
+        std::fs::write(index_path(&file.0), b"OXDNAIDX").unwrap();
+        assert!(load_frame_index(&file.0, &options).unwrap().is_none());
+    }
+}
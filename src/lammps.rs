@@ -0,0 +1,553 @@
+use numpy::ndarray::{s, ArrayView1, ArrayView2};
+use std::fs::File;
+use std::io::{
+    BufRead, BufReader, BufWriter, Error, ErrorKind::InvalidInput, Seek, SeekFrom, Write,
+};
+
+use super::{open_uncompressed, Configuration};
+
+// dump of the LAMMPS oxdna package, e.g. from
+// `dump custom id type x y z ix iy iz vx vy vz c_quat[1] c_quat[2] c_quat[3] c_quat[4] angmomx angmomy angmomz`,
+// where orientations are quaternions instead of a1 and a3 vectors
+struct DumpColumns {
+    id: usize,
+    position: [usize; 3],
+    image: Option<[usize; 3]>,
+    velocity: Option<[usize; 3]>,
+    // w, i, j, k
+    quaternion: [usize; 4],
+    angular_momentum: Option<[usize; 3]>,
+}
+
+impl DumpColumns {
+    fn find(names: &[&str]) -> Result<Self, Error> {
+        let find = |name: &str| names.iter().position(|column| *column == name);
+        let find_all = |group: [&str; 3]| -> Option<[usize; 3]> {
+            Some([find(group[0])?, find(group[1])?, find(group[2])?])
+        };
+        let missing = |what: &str| {
+            Error::new(
+                InvalidInput,
+                format!(
+                    "LAMMPS dump has no {} columns in ATOMS {}",
+                    what,
+                    names.join(" ")
+                ),
+            )
+        };
+        let position = find_all(["x", "y", "z"])
+            .or_else(|| find_all(["xu", "yu", "zu"]))
+            .ok_or_else(|| missing("x y z or xu yu zu"))?;
+        // per-atom quaternion properties, or a compute such as c_quat[1] to c_quat[4]
+        let quaternion = match [find("quatw"), find("quati"), find("quatj"), find("quatk")] {
+            [Some(w), Some(i), Some(j), Some(k)] => Some([w, i, j, k]),
+            _ => names.iter().find_map(|name| {
+                let prefix = name.strip_suffix("[1]")?;
+                Some([
+                    find(&format!("{}[1]", prefix))?,
+                    find(&format!("{}[2]", prefix))?,
+                    find(&format!("{}[3]", prefix))?,
+                    find(&format!("{}[4]", prefix))?,
+                ])
+            }),
+        }
+        .ok_or_else(|| missing("quatw quati quatj quatk or quaternion compute"))?;
+        Ok(Self {
+            id: find("id").ok_or_else(|| missing("id"))?,
+            position,
+            // unwrapped positions need no image flags
+            image: find_all(["ix", "iy", "iz"]).filter(|_| find("x").is_some()),
+            velocity: find_all(["vx", "vy", "vz"]),
+            quaternion,
+            angular_momentum: find_all(["angmomx", "angmomy", "angmomz"]),
+        })
+    }
+}
+
+// a1 and a3 are the x and z axes of the nucleotide frame, the first and third columns of the rotation matrix
+fn quaternion_to_a1_a3([w, x, y, z]: [f64; 4]) -> [f64; 6] {
+    [
+        w * w + x * x - y * y - z * z,
+        2.0 * (x * y + w * z),
+        2.0 * (x * z - w * y),
+        2.0 * (x * z + w * y),
+        2.0 * (y * z - w * x),
+        w * w - x * x - y * y + z * z,
+    ]
+}
+
+fn invalid_dump(message: String) -> Error {
+    Error::new(InvalidInput, format!("Invalid LAMMPS dump: {}", message))
+}
+
+struct DumpLines {
+    reader: BufReader<File>,
+    line: String,
+    cursor_offset: u64,
+}
+
+impl DumpLines {
+    // the trimmed next line, None at end of file
+    fn next_line(&mut self) -> Result<Option<&str>, Error> {
+        self.line.clear();
+        let bytes_read = self.reader.read_line(&mut self.line)?;
+        self.cursor_offset += bytes_read as u64;
+        Ok((bytes_read > 0).then(|| self.line.trim()))
+    }
+
+    fn expect_line(&mut self, what: &str) -> Result<&str, Error> {
+        self.next_line()?
+            .ok_or_else(|| invalid_dump(format!("end of file before {}", what)))
+    }
+
+    fn expect_value<T: std::str::FromStr>(&mut self, what: &str) -> Result<T, Error> {
+        let line = self.expect_line(what)?;
+        line.parse()
+            .map_err(|_| invalid_dump(format!("invalid {} \"{}\"", what, line)))
+    }
+}
+
+// configurations with nucleotides sorted by atom id, time from TIMESTEP and box edges from BOX BOUNDS, with
+// positions unwrapped by image flags if present, 15 columns if velocities or angular momenta are dumped
+// and 9 otherwise, and no energy values as dumps have none
+pub fn read_lammps_dump(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+) -> Result<Vec<(u64, Configuration)>, Error> {
+    let mut reader = BufReader::new(open_uncompressed(file_path)?);
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut lines = DumpLines {
+        reader,
+        line: String::new(),
+        cursor_offset: offset,
+    };
+    let mut configs = Vec::new();
+    let mut time = None;
+    let mut n_atoms = None;
+    let mut cbox = None;
+    while configs.len() < limit {
+        let Some(line) = lines.next_line()? else {
+            break;
+        };
+        // lines of other sections, e.g. ITEM: UNITS, are passed over
+        let Some(item) = line.strip_prefix("ITEM:") else {
+            continue;
+        };
+        let item = item.trim().to_string();
+        if item == "TIMESTEP" {
+            time = Some(lines.expect_value::<u64>("timestep")?);
+        } else if item == "NUMBER OF ATOMS" {
+            n_atoms = Some(lines.expect_value::<usize>("number of atoms")?);
+        } else if let Some(boundaries) = item.strip_prefix("BOX BOUNDS") {
+            if boundaries.contains("xy") {
+                return Err(invalid_dump(format!(
+                    "triclinic box \"{}\", oxDNA boxes are orthogonal",
+                    item
+                )));
+            }
+            let mut edges = Vec::with_capacity(3);
+            for _ in 0..3 {
+                let line = lines.expect_line("box bounds")?;
+                let bounds = line
+                    .split_whitespace()
+                    .map(|value| value.parse::<f64>().ok())
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|bounds| bounds.len() == 2)
+                    .ok_or_else(|| invalid_dump(format!("invalid box bounds \"{}\"", line)))?;
+                edges.push(bounds[1] - bounds[0]);
+            }
+            cbox = Some(edges);
+        } else if let Some(names) = item.strip_prefix("ATOMS") {
+            let (Some(time), Some(n_atoms), Some(cbox)) =
+                (time.take(), n_atoms.take(), cbox.take())
+            else {
+                return Err(invalid_dump(
+                    "ATOMS section before TIMESTEP, NUMBER OF ATOMS and BOX BOUNDS".to_string(),
+                ));
+            };
+            let names = names.split_whitespace().collect::<Vec<_>>();
+            let columns = DumpColumns::find(&names)?;
+            let n_columns = match (columns.velocity, columns.angular_momentum) {
+                (None, None) => 9,
+                _ => 15,
+            };
+            let mut atoms = Vec::with_capacity(n_atoms);
+            for _ in 0..n_atoms {
+                let line = lines.expect_line("all atoms of a timestep")?;
+                let values = line
+                    .split_whitespace()
+                    .map(|value| value.parse::<f64>().ok())
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|values| values.len() == names.len())
+                    .ok_or_else(|| {
+                        invalid_dump(format!(
+                            "atom line \"{}\" of timestep {} does not have {} values",
+                            line,
+                            time,
+                            names.len()
+                        ))
+                    })?;
+                let mut nucleotide = Vec::with_capacity(n_columns);
+                for (axis, &column) in columns.position.iter().enumerate() {
+                    let image = columns.image.map_or(0.0, |image| values[image[axis]]);
+                    nucleotide.push(values[column] + image * cbox[axis]);
+                }
+                nucleotide.extend(quaternion_to_a1_a3(columns.quaternion.map(|c| values[c])));
+                if n_columns == 15 {
+                    for group in [columns.velocity, columns.angular_momentum] {
+                        match group {
+                            Some(group) => nucleotide.extend(group.map(|c| values[c])),
+                            None => nucleotide.extend([0.0; 3]),
+                        }
+                    }
+                }
+                atoms.push((values[columns.id] as u64, nucleotide));
+            }
+            atoms.sort_by_key(|&(id, _)| id);
+            configs.push((
+                lines.cursor_offset,
+                Configuration {
+                    time,
+                    cbox,
+                    cenergy: Vec::new(),
+                    nucleotides: atoms
+                        .into_iter()
+                        .map(|(_, nucleotide)| nucleotide)
+                        .collect(),
+                    extra_columns: Vec::new(),
+                },
+            ));
+        }
+    }
+    Ok(configs)
+}
+
+// inverse of quaternion_to_a1_a3, from the rotation matrix with columns a1, a3 x a1 and a3
+fn a1_a3_to_quaternion(a1: [f64; 3], a3: [f64; 3]) -> [f64; 4] {
+    let a2 = [
+        a3[1] * a1[2] - a3[2] * a1[1],
+        a3[2] * a1[0] - a3[0] * a1[2],
+        a3[0] * a1[1] - a3[1] * a1[0],
+    ];
+    let m = |row: usize, column: usize| [a1, a2, a3][column][row];
+    let trace = m(0, 0) + m(1, 1) + m(2, 2);
+    // divides by the largest of the four candidates for numerical stability
+    let [w, x, y, z] = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [
+            s / 4.0,
+            (m(2, 1) - m(1, 2)) / s,
+            (m(0, 2) - m(2, 0)) / s,
+            (m(1, 0) - m(0, 1)) / s,
+        ]
+    } else if m(0, 0) > m(1, 1) && m(0, 0) > m(2, 2) {
+        let s = (1.0 + m(0, 0) - m(1, 1) - m(2, 2)).sqrt() * 2.0;
+        [
+            (m(2, 1) - m(1, 2)) / s,
+            s / 4.0,
+            (m(0, 1) + m(1, 0)) / s,
+            (m(0, 2) + m(2, 0)) / s,
+        ]
+    } else if m(1, 1) > m(2, 2) {
+        let s = (1.0 + m(1, 1) - m(0, 0) - m(2, 2)).sqrt() * 2.0;
+        [
+            (m(0, 2) - m(2, 0)) / s,
+            (m(0, 1) + m(1, 0)) / s,
+            s / 4.0,
+            (m(1, 2) + m(2, 1)) / s,
+        ]
+    } else {
+        let s = (1.0 + m(2, 2) - m(0, 0) - m(1, 1)).sqrt() * 2.0;
+        [
+            (m(1, 0) - m(0, 1)) / s,
+            (m(0, 2) + m(2, 0)) / s,
+            (m(1, 2) + m(2, 1)) / s,
+            s / 4.0,
+        ]
+    };
+    let norm = (w * w + x * x + y * y + z * z).sqrt();
+    [w / norm, x / norm, y / norm, z / norm]
+}
+
+// atom types of the LAMMPS oxdna package
+fn lammps_atom_type(base: char) -> Option<usize> {
+    match base {
+        'A' => Some(1),
+        'C' => Some(2),
+        'G' => Some(3),
+        'T' | 'U' => Some(4),
+        _ => None,
+    }
+}
+
+// mass and ellipsoid shape of every nucleotide in the LAMMPS oxdna examples
+const LAMMPS_NUCLEOTIDE_MASS: f64 = 3.1575;
+const LAMMPS_NUCLEOTIDE_SHAPE: f64 = 1.1739845031423408;
+
+// data file for `atom_style hybrid bond ellipsoid` with an atom per nucleotide typed by its base,
+// a molecule per strand and a bond from each nucleotide to the next one of its strand,
+// positions wrapped into a box from the origin with image flags, and velocities if nucleotides have 15 columns
+pub fn write_lammps_data(
+    file_path: &str,
+    time: u64,
+    box_array: ArrayView1<'_, f64>,
+    nucleotides_array: ArrayView2<'_, f64>,
+    strands: &[(usize, usize, String)],
+) -> Result<(), Error> {
+    if box_array.len() < 3 {
+        return Err(Error::new(
+            InvalidInput,
+            format!("Box array needs 3 values, got {}", box_array.len()),
+        ));
+    }
+    let n_nucleotides = super::check_strand_layout(strands)?;
+    if nucleotides_array.nrows() != n_nucleotides || nucleotides_array.ncols() < 9 {
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "Configuration at time {} has {} nucleotides of {} columns, expected {} of at least 9",
+                time,
+                nucleotides_array.nrows(),
+                nucleotides_array.ncols(),
+                n_nucleotides
+            ),
+        ));
+    }
+    let n_bonds = strands
+        .iter()
+        .map(|(start, end, _)| end + 1 - start)
+        .map(|length| length.saturating_sub(1))
+        .sum::<usize>();
+    let mut output = BufWriter::new(File::create(file_path)?);
+    writeln!(
+        output,
+        "LAMMPS data file of oxDNA configuration at time {}\n",
+        time
+    )?;
+    writeln!(output, "{} atoms", n_nucleotides)?;
+    writeln!(output, "{} ellipsoids", n_nucleotides)?;
+    writeln!(output, "{} bonds\n", n_bonds)?;
+    writeln!(output, "4 atom types")?;
+    writeln!(output, "1 bond types\n")?;
+    for (axis, name) in ["x", "y", "z"].iter().enumerate() {
+        writeln!(output, "0 {} {}lo {}hi", box_array[axis], name, name)?;
+    }
+    writeln!(output, "\nMasses\n")?;
+    for atom_type in 1..=4 {
+        writeln!(output, "{} {}", atom_type, LAMMPS_NUCLEOTIDE_MASS)?;
+    }
+    writeln!(output, "\nAtoms # hybrid bond ellipsoid\n")?;
+    for (molecule, (start, end, sequence)) in strands.iter().enumerate() {
+        for (index, base) in (*start..=*end).zip(sequence.chars()) {
+            let atom_type = lammps_atom_type(base).ok_or_else(|| {
+                Error::new(
+                    InvalidInput,
+                    format!(
+                        "Nucleotide {} has base {}, expected A, C, G, T or U",
+                        index, base
+                    ),
+                )
+            })?;
+            let nucleotide = nucleotides_array.row(index);
+            let mut wrapped = [0.0; 3];
+            let mut images = [0i64; 3];
+            for axis in 0..3 {
+                let image = (nucleotide[axis] / box_array[axis]).floor();
+                wrapped[axis] = nucleotide[axis] - image * box_array[axis];
+                images[axis] = image as i64;
+            }
+            writeln!(
+                output,
+                "{} {} {} {} {} {} 1 1 {} {} {}",
+                index + 1,
+                atom_type,
+                wrapped[0],
+                wrapped[1],
+                wrapped[2],
+                molecule + 1,
+                images[0],
+                images[1],
+                images[2]
+            )?;
+        }
+    }
+    if nucleotides_array.ncols() >= 15 {
+        // oxDNA angular velocities are written as angular momenta
+        writeln!(output, "\nVelocities\n")?;
+        for (index, nucleotide) in nucleotides_array.outer_iter().enumerate() {
+            let values = nucleotide
+                .slice(s![9..15])
+                .iter()
+                .map(f64::to_string)
+                .collect::<Vec<_>>();
+            writeln!(output, "{} {}", index + 1, values.join(" "))?;
+        }
+    }
+    writeln!(output, "\nEllipsoids\n")?;
+    for (index, nucleotide) in nucleotides_array.outer_iter().enumerate() {
+        let [w, x, y, z] = a1_a3_to_quaternion(
+            [nucleotide[3], nucleotide[4], nucleotide[5]],
+            [nucleotide[6], nucleotide[7], nucleotide[8]],
+        );
+        writeln!(
+            output,
+            "{} {} {} {} {} {} {} {}",
+            index + 1,
+            LAMMPS_NUCLEOTIDE_SHAPE,
+            LAMMPS_NUCLEOTIDE_SHAPE,
+            LAMMPS_NUCLEOTIDE_SHAPE,
+            w,
+            x,
+            y,
+            z
+        )?;
+    }
+    if n_bonds > 0 {
+        // bonds point from 3' to 5', the order of nucleotides in a strand
+        writeln!(output, "\nBonds\n")?;
+        let bonds = strands
+            .iter()
+            .flat_map(|&(start, end, _)| (start..end).map(|index| (index, index + 1)));
+        for (bond, (from, to)) in bonds.enumerate() {
+            writeln!(output, "{} 1 {} {}", bond + 1, from + 1, to + 1)?;
+        }
+    }
+    output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use numpy::ndarray::{arr1, Array2};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static FILE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    // file in the temporary directory, removed when dropped
+    struct TempFile(String);
+
+    impl TempFile {
+        fn new(contents: impl AsRef<[u8]>) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "oxdna_lammps_{}_{}",
+                std::process::id(),
+                FILE_COUNT.fetch_add(1, Ordering::Relaxed)
+            ));
+            std::fs::write(&path, contents).unwrap();
+            Self(path.to_string_lossy().into_owned())
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn assert_close(a: &[f64], b: &[f64]) {
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(b) {
+            assert!((a - b).abs() < 1e-12, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn converts_orientations_to_quaternions_and_back() {
+        let diagonal = std::f64::consts::FRAC_1_SQRT_2;
+        for (a1, a3) in [
+            ([1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+            ([diagonal, diagonal, 0.0], [0.0, 0.0, 1.0]),
+            ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+            ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+            ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0]),
+            ([0.0, 0.0, 1.0], [diagonal, -diagonal, 0.0]),
+        ] {
+            let quaternion = a1_a3_to_quaternion(a1, a3);
+            assert_close(&quaternion_to_a1_a3(quaternion), &[a1, a3].concat());
+        }
+        assert_eq!(
+            a1_a3_to_quaternion([1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+            [1.0, 0.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn reads_dump_sorted_by_id_with_unwrapped_positions() {
+        let file = TempFile::new(
+            "ITEM: TIMESTEP\n100\nITEM: NUMBER OF ATOMS\n2\nITEM: BOX BOUNDS pp pp pp\n\
+             -10 10\n-10 10\n-10 10\n\
+             ITEM: ATOMS id type x y z ix iy iz c_quat[1] c_quat[2] c_quat[3] c_quat[4]\n\
+             2 1 1 2 3 1 0 0 1 0 0 0\n\
+             1 4 -1 0 0 0 0 0 0 0 0 1\n\
+             ITEM: TIMESTEP\n200\nITEM: NUMBER OF ATOMS\n1\nITEM: BOX BOUNDS pp pp pp\n\
+             0 10\n0 10\n0 10\n\
+             ITEM: ATOMS id xu yu zu quatw quati quatj quatk vx vy vz\n\
+             1 1 2 3 1 0 0 0 0.5 0 0\n",
+        );
+        let configs = read_lammps_dump(&file.0, 0, 10).unwrap();
+        assert_eq!(configs.len(), 2);
+        let (_, conf) = &configs[0];
+        assert_eq!((conf.time, conf.cbox.clone()), (100, vec![20.0; 3]));
+        assert!(conf.cenergy.is_empty());
+        // rotated half a turn about z
+        assert_close(
+            &conf.nucleotides[0],
+            &[-1.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+        );
+        assert_close(
+            &conf.nucleotides[1],
+            &[21.0, 2.0, 3.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+        );
+        let (end_offset, conf) = &configs[1];
+        assert_eq!(*end_offset, std::fs::metadata(&file.0).unwrap().len());
+        assert_eq!(conf.time, 200);
+        assert_close(
+            &conf.nucleotides[0],
+            &[
+                1.0, 2.0, 3.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0,
+            ],
+        );
+        let resumed = read_lammps_dump(&file.0, configs[0].0, 10).unwrap();
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].1.time, 200);
+    }
+
+    #[test]
+    fn rejects_dumps_without_orientations() {
+        let file = TempFile::new(
+            "ITEM: TIMESTEP\n0\nITEM: NUMBER OF ATOMS\n1\nITEM: BOX BOUNDS pp pp pp\n\
+             0 10\n0 10\n0 10\nITEM: ATOMS id x y z\n1 1 2 3\n",
+        );
+        assert!(read_lammps_dump(&file.0, 0, 10).is_err());
+        let compressed = TempFile::new([0x1f, 0x8b, 0x08, 0x00]);
+        assert!(read_lammps_dump(&compressed.0, 0, 10).is_err());
+    }
+
+    #[test]
+    fn writes_data_file_with_wrapped_positions_and_bonds() {
+        let nucleotides = Array2::from_shape_vec(
+            (2, 9),
+            vec![
+                -1.0, 2.0, 3.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, //
+                1.0, 2.0, 3.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+            ],
+        )
+        .unwrap();
+        let cbox = arr1(&[20.0, 20.0, 20.0]);
+        let output = TempFile::new("");
+        let strands = [(0, 1, "AT".to_string())];
+        write_lammps_data(&output.0, 7, cbox.view(), nucleotides.view(), &strands).unwrap();
+        let data = std::fs::read_to_string(&output.0).unwrap();
+        assert!(data.contains("\n2 atoms\n2 ellipsoids\n1 bonds\n"));
+        assert!(data.contains("\n1 1 19 2 3 1 1 1 -1 0 0\n2 4 1 2 3 1 1 1 0 0 0\n"));
+        assert!(data.contains("\nBonds\n\n1 1 1 2\n"));
+        assert!(!data.contains("Velocities"));
+        assert_eq!(lammps_atom_type('U'), Some(4));
+        assert_eq!(lammps_atom_type('X'), None);
+        let strands = [(0, 1, "AX".to_string())];
+        assert!(
+            write_lammps_data(&output.0, 7, cbox.view(), nucleotides.view(), &strands).is_err()
+        );
+    }
+}
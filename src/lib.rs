@@ -1,26 +1,306 @@
-use numpy::{PyArray1, PyArray2, PyArrayMethods};
+use memmap2::Mmap;
+use numpy::{PyArray1, PyArray2, PyArray3, PyArrayMethods};
+use pyo3::create_exception;
 use pyo3::exceptions::{PyIOError, PyValueError};
 use pyo3::prelude::*;
-use rayon::iter::{IntoParallelRefIterator, ParallelBridge, ParallelIterator};
+use pyo3::types::{PyDict, PyIterator, PyList, PyTuple};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelBridge,
+    ParallelIterator,
+};
+use rayon::slice::ParallelSlice;
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Error, ErrorKind::InvalidInput, Seek, SeekFrom};
+use std::io::{BufRead, BufReader, Error, ErrorKind::InvalidInput, Read, Seek, SeekFrom, Write};
+use std::sync::{mpsc, Arc, RwLock};
 
-struct LineReader {
-    reader: BufReader<File>,
+// one float parsing backend at a time, lexical and std-parse need --no-default-features
+#[cfg(all(feature = "fast_float", feature = "lexical"))]
+compile_error!("features `fast_float` and `lexical` select conflicting float parsing backends, enable only one");
+#[cfg(all(
+    feature = "std-parse",
+    any(feature = "fast_float", feature = "lexical")
+))]
+compile_error!(
+    "feature `std-parse` conflicts with the `fast_float` and `lexical` float parsing backends"
+);
+
+mod index;
+mod lammps;
+mod topology;
+pub use index::{build_frame_index, load_frame_index, save_frame_index, IndexEntry};
+pub use lammps::{read_lammps_dump, write_lammps_data};
+pub use topology::{read_topology_file, Topology};
+
+// where a format error is in the input, each part filled in by whichever caller knows it
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ErrorLocation {
+    // position of the configuration among those read, counting from the first one read
+    pub index: Option<usize>,
+    // 1-based line number in the file
+    pub line_number: Option<u64>,
+    // byte offset of the line in the file
+    pub offset: Option<u64>,
+    // line within the configuration in its order after reading, time header first,
+    // until turned into an offset once the configuration start is known
+    frame_line: Option<usize>,
+}
+
+impl ErrorLocation {
+    fn at_frame_line(frame_line: usize) -> Self {
+        Self {
+            frame_line: Some(frame_line),
+            ..Self::default()
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts = [
+            self.index.map(|index| format!("configuration {}", index)),
+            self.line_number
+                .map(|line_number| format!("line {}", line_number)),
+            self.offset.map(|offset| format!("byte offset {}", offset)),
+        ];
+        let parts = parts.into_iter().flatten().collect::<Vec<_>>();
+        if parts.is_empty() {
+            return Ok(());
+        }
+        write!(f, " ({})", parts.join(", "))
+    }
+}
+
+#[derive(Debug)]
+pub enum TrajectoryError {
+    Io(Error),
+    // header line absent, or not a `key = values` line for the expected key
+    MissingHeader {
+        header: String,
+        line: Option<String>,
+        location: Box<ErrorLocation>,
+    },
+    // frame is the time of the configuration, when known
+    BadValue {
+        frame: Option<u64>,
+        line: String,
+        value: String,
+        location: Box<ErrorLocation>,
+    },
+    CountMismatch {
+        frame: Option<u64>,
+        name: String,
+        line: Option<String>,
+        expected: usize,
+        found: usize,
+        location: Box<ErrorLocation>,
+    },
+}
+
+impl TrajectoryError {
+    // fills in the configuration time of an error from parsing one of its lines
+    fn at_frame(mut e: Error, time: u64) -> Error {
+        if let Some(
+            TrajectoryError::BadValue { frame, .. } | TrajectoryError::CountMismatch { frame, .. },
+        ) = e
+            .get_mut()
+            .and_then(|inner| inner.downcast_mut::<TrajectoryError>())
+        {
+            frame.get_or_insert(time);
+        }
+        e
+    }
+
+    fn location_mut(e: &mut Error) -> Option<&mut ErrorLocation> {
+        match e.get_mut()?.downcast_mut::<TrajectoryError>()? {
+            TrajectoryError::Io(_) => None,
+            TrajectoryError::MissingHeader { location, .. }
+            | TrajectoryError::BadValue { location, .. }
+            | TrajectoryError::CountMismatch { location, .. } => Some(location),
+        }
+    }
+
+    // records which line of its configuration an error comes from, 0 for the time header
+    fn at_frame_line(mut e: Error, frame_line: usize) -> Error {
+        if let Some(location) = Self::location_mut(&mut e) {
+            location.frame_line.get_or_insert(frame_line);
+        }
+        e
+    }
+
+    // turns the line within the configuration into a byte offset, lines keep their line endings
+    fn at_frame_start(
+        mut e: Error,
+        start: u64,
+        lines: &[String],
+        header_position: HeaderPosition,
+    ) -> Error {
+        if let Some(location) = Self::location_mut(&mut e) {
+            if let (None, Some(frame_line)) = (location.offset, location.frame_line) {
+                let length = |line: &String| line.len() as u64;
+                // trailing headers were moved in front of the body lines, which come first in the file
+                let before: u64 = match header_position {
+                    HeaderPosition::Leading => lines.iter().take(frame_line).map(length).sum(),
+                    HeaderPosition::Trailing if frame_line < 3 => lines
+                        .iter()
+                        .skip(3)
+                        .chain(lines.iter().take(frame_line))
+                        .map(length)
+                        .sum(),
+                    HeaderPosition::Trailing => {
+                        lines.iter().skip(3).take(frame_line - 3).map(length).sum()
+                    }
+                };
+                location.offset = Some(start + before);
+            }
+        }
+        e
+    }
+
+    // fills in the position of the configuration among those read
+    fn at_index(mut e: Error, index: usize) -> Error {
+        if let Some(location) = Self::location_mut(&mut e) {
+            location.index.get_or_insert(index);
+        }
+        e
+    }
+
+    // fills in the line number of an error with a known offset by counting the lines before it,
+    // only for uncompressed files as offsets of compressed ones are in the decompressed data
+    fn with_line_number(mut e: Error, file_path: &str) -> Error {
+        if let Some(location) = Self::location_mut(&mut e) {
+            if let (None, Some(offset)) = (location.line_number, location.offset) {
+                location.line_number = count_lines_before(file_path, offset).ok().flatten();
+            }
+        }
+        e
+    }
+}
+
+fn count_lines_before(file_path: &str, offset: u64) -> Result<Option<u64>, Error> {
+    if detect_compression(file_path)?.is_some() {
+        return Ok(None);
+    }
+    let mut reader = BufReader::new(File::open(file_path)?).take(offset);
+    let mut newlines = 0;
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            break;
+        }
+        newlines += buffer.iter().filter(|&&byte| byte == b'\n').count() as u64;
+        let consumed = buffer.len();
+        reader.consume(consumed);
+    }
+    Ok(Some(newlines + 1))
+}
+
+impl std::fmt::Display for TrajectoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (frame, location) = match self {
+            TrajectoryError::Io(e) => return e.fmt(f),
+            TrajectoryError::MissingHeader {
+                header,
+                line: None,
+                location,
+            } => {
+                write!(f, "Missing {} header line", header)?;
+                (&None, location)
+            }
+            TrajectoryError::MissingHeader {
+                header,
+                line: Some(line),
+                location,
+            } => {
+                write!(f, "Invalid {} header line: {}", header, line)?;
+                (&None, location)
+            }
+            TrajectoryError::BadValue {
+                frame,
+                line,
+                value,
+                location,
+            } => {
+                write!(f, "Invalid value \"{}\" in \"{}\"", value, line)?;
+                (frame, location)
+            }
+            TrajectoryError::CountMismatch {
+                frame,
+                name,
+                line,
+                expected,
+                found,
+                location,
+            } => {
+                write!(f, "Expected {} {} values, found {}", expected, name, found)?;
+                if let Some(line) = line {
+                    write!(f, " in \"{}\"", line)?;
+                }
+                (frame, location)
+            }
+        };
+        if let Some(time) = frame {
+            write!(f, " of configuration at time {}", time)?;
+        }
+        location.fmt(f)
+    }
+}
+
+impl std::error::Error for TrajectoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TrajectoryError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+// format errors travel as InvalidInput io errors, so functions returning io::Error keep using `?`
+impl From<TrajectoryError> for Error {
+    fn from(e: TrajectoryError) -> Self {
+        match e {
+            TrajectoryError::Io(e) => e,
+            e => Error::new(InvalidInput, e),
+        }
+    }
+}
+
+impl From<Error> for TrajectoryError {
+    fn from(e: Error) -> Self {
+        if e.get_ref()
+            .is_some_and(|inner| inner.is::<TrajectoryError>())
+        {
+            let inner = e.into_inner().expect("checked to have an inner error");
+            return *inner
+                .downcast::<TrajectoryError>()
+                .expect("checked to be a TrajectoryError");
+        }
+        TrajectoryError::Io(e)
+    }
+}
+
+struct LineReader<R: BufRead = BufReader<File>> {
+    reader: R,
     line: String,
     reached_end: bool,
     got_error: bool,
-    bytes_read: usize,
-    cursor_offset: usize,
-    line_start_offset: usize,
+    bytes_read: u64,
+    cursor_offset: u64,
+    line_start_offset: u64,
 }
 
 impl LineReader {
-    fn new(file_path: &str, offset: usize) -> Result<Self, Error> {
-        let file = File::open(file_path)?;
+    fn new(file_path: &str, offset: u64) -> Result<Self, Error> {
+        let file = open_uncompressed(file_path)?;
         let mut reader = BufReader::new(file);
-        reader.seek(SeekFrom::Start(offset as u64))?;
-        Ok(Self {
+        reader.seek(SeekFrom::Start(offset))?;
+        Ok(Self::from_reader(reader, offset))
+    }
+}
+
+impl<R: BufRead> LineReader<R> {
+    fn from_reader(reader: R, offset: u64) -> Self {
+        Self {
             reader,
             line: String::new(),
             reached_end: false,
@@ -28,15 +308,15 @@ impl LineReader {
             bytes_read: 0,
             cursor_offset: offset,
             line_start_offset: offset,
-        })
+        }
     }
     fn read_line(&mut self) -> Result<(), Error> {
         self.line.clear();
         self.line_start_offset = self.cursor_offset;
         match self.reader.read_line(&mut self.line) {
             Ok(bytes_read) => {
-                self.bytes_read = bytes_read;
-                self.cursor_offset += bytes_read;
+                self.bytes_read = bytes_read as u64;
+                self.cursor_offset += bytes_read as u64;
                 if bytes_read == 0 {
                     self.reached_end = true;
                 }
@@ -55,238 +335,4741 @@ impl LineReader {
     }
 }
 
-struct ConfigReader {
-    reader: LineReader,
-    save_lines: bool,
+#[derive(Clone, Copy, PartialEq)]
+enum SaveLines {
+    None,
+    Headers,
+    All,
+}
+
+impl SaveLines {
+    fn keeps(&self, saved: usize) -> bool {
+        match self {
+            SaveLines::None => false,
+            SaveLines::Headers => saved < 3,
+            SaveLines::All => true,
+        }
+    }
+}
+
+struct ConfigReader<R: BufRead = BufReader<File>> {
+    reader: LineReader<R>,
+    save_lines: SaveLines,
+    case_insensitive_headers: bool,
+    require_frame_start: bool,
+    end_offset: Option<u64>,
+    header_position: HeaderPosition,
+    // number of lines of the last returned configuration, saved or not
+    line_count: usize,
+    // offset of the first nucleotide line of the last returned configuration,
+    // its end offset if it has no nucleotide lines
+    body_offset: u64,
+    stride: usize,
+    // configurations to pass over before returning the next one
+    frames_to_skip: usize,
+    // file read, for line numbers of errors, None when reading a stream
+    file_path: Option<String>,
 }
 
 impl ConfigReader {
-    fn new(filepath: &str, offset: usize, save_lines: bool) -> Result<Self, Error> {
+    fn new(
+        filepath: &str,
+        offset: u64,
+        save_lines: SaveLines,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
         Ok(Self {
-            reader: LineReader::new(filepath, offset)?,
-            save_lines,
+            file_path: Some(filepath.to_string()),
+            ..Self::from_reader(LineReader::new(filepath, offset)?, save_lines, options)
         })
     }
 }
 
-impl Iterator for ConfigReader {
-    type Item = Result<(usize, usize, Vec<String>), Error>;
+impl<R: BufRead> ConfigReader<R> {
+    fn from_reader(reader: LineReader<R>, save_lines: SaveLines, options: &ParseOptions) -> Self {
+        Self {
+            reader,
+            save_lines,
+            case_insensitive_headers: options.case_insensitive_headers,
+            require_frame_start: options.require_frame_start,
+            end_offset: options.end_offset,
+            header_position: options.header_position,
+            line_count: 0,
+            body_offset: 0,
+            stride: options.stride,
+            frames_to_skip: 0,
+            file_path: None,
+        }
+    }
+
+    fn with_line_number(&self, e: Error) -> Error {
+        match &self.file_path {
+            Some(file_path) => TrajectoryError::with_line_number(e, file_path),
+            None => e,
+        }
+    }
+
+    fn read_line_or_end(&mut self) -> Result<bool, Error> {
+        self.reader.read_line()?;
+        Ok(!self.reader.reached_end)
+    }
+
+    // body lines come first and the configuration ends with its t, b, E lines,
+    // which are moved to the front so lines are in the usual order
+    fn next_trailing(&mut self) -> Option<<Self as Iterator>::Item> {
+        if self.reader.reached_end || self.reader.got_error {
+            return None;
+        }
+        let mut body = Vec::new();
+        let mut config_start = None;
+        let mut has_body_values = false;
+        loop {
+            match self.read_line_or_end() {
+                Err(e) => return Some(Err(e)),
+                Ok(false) if !has_body_values => return None,
+                Ok(false) => {
+                    return Some(Err(Error::new(
+                        InvalidInput,
+                        format!(
+                            "Configuration at offset {} has no headers before end of file",
+                            config_start.unwrap_or(self.reader.line_start_offset)
+                        ),
+                    )))
+                }
+                Ok(true) => {}
+            }
+            let line_start = *config_start.get_or_insert(self.reader.line_start_offset);
+            if self
+                .end_offset
+                .is_some_and(|end_offset| line_start >= end_offset)
+            {
+                self.reader.reached_end = true;
+                return None;
+            }
+            if self.at_frame_start() {
+                break;
+            }
+            has_body_values |= !self.reader.line.trim().is_empty();
+            if self.save_lines == SaveLines::All {
+                body.push(self.reader.take_line());
+            }
+        }
+        let mut lines = Vec::with_capacity(3 + body.len());
+        self.line_count = body.len() + 3;
+        self.body_offset = config_start.unwrap_or_default();
+        for header in 0..3 {
+            if header > 0 {
+                match self.read_line_or_end() {
+                    Err(e) => return Some(Err(e)),
+                    Ok(false) => break,
+                    Ok(true) => {}
+                }
+            }
+            if self.save_lines != SaveLines::None {
+                lines.push(self.reader.take_line());
+            }
+        }
+        lines.append(&mut body);
+        Some(Ok((
+            config_start.unwrap_or_default(),
+            self.reader.cursor_offset,
+            lines,
+        )))
+    }
+
+    // true once the last configuration returned ran into end of file,
+    // rather than stopping at the next configuration or the end offset
+    fn at_end_of_input(&self) -> bool {
+        self.reader.reached_end && !self.reader.got_error && self.reader.line.is_empty()
+    }
+
+    fn at_frame_start(&self) -> bool {
+        let line = self.reader.line.trim_start_matches([' ', '\t']);
+        if self.case_insensitive_headers {
+            line.starts_with(['t', 'T'])
+        } else {
+            line.starts_with('t')
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for ConfigReader<R> {
+    type Item = Result<(u64, u64, Vec<String>), Error>;
     fn next(&mut self) -> Option<Self::Item> {
+        if self.frames_to_skip > 0 {
+            // skipped configurations are only scanned for where the next one starts
+            let save_lines = std::mem::replace(&mut self.save_lines, SaveLines::None);
+            while self.frames_to_skip > 0 {
+                self.frames_to_skip -= 1;
+                match self.next_frame() {
+                    Some(Ok(_)) => {}
+                    other => {
+                        self.save_lines = save_lines;
+                        return other;
+                    }
+                }
+            }
+            self.save_lines = save_lines;
+        }
+        self.frames_to_skip = self.stride.saturating_sub(1);
+        self.next_frame()
+    }
+}
+
+impl<R: BufRead> ConfigReader<R> {
+    fn next_frame(&mut self) -> Option<<Self as Iterator>::Item> {
+        if self.header_position == HeaderPosition::Trailing {
+            return self.next_trailing();
+        }
         let mut lines = Vec::new();
         if self.reader.reached_end || self.reader.got_error {
             return None;
         }
-        while !self.reader.line.starts_with('t') {
+        // an offset inside a configuration skips forward to the next one, unless required to be at one
+        while !self.at_frame_start() {
+            if !self.case_insensitive_headers
+                && is_uppercase_time_header(self.reader.line.as_bytes())
+            {
+                self.reader.reached_end = true;
+                return Some(Err(uppercase_header_error(
+                    self.reader.line_start_offset,
+                    &self.reader.line,
+                )));
+            }
             if let Err(e) = self.reader.read_line() {
                 return Some(Err(e));
             }
             if self.reader.reached_end || self.reader.got_error {
                 return None;
             }
+            if self.require_frame_start && !self.at_frame_start() {
+                self.reader.reached_end = true;
+                return Some(Err(Error::new(
+                    InvalidInput,
+                    format!(
+                        "Offset {} is not at start of a configuration: {}",
+                        self.reader.line_start_offset,
+                        self.reader.line.trim_end()
+                    ),
+                )));
+            }
         }
         let config_start = self.reader.line_start_offset;
-        if self.save_lines {
+        // a configuration starting exactly at end offset belongs to the next byte range
+        if self
+            .end_offset
+            .is_some_and(|end_offset| config_start >= end_offset)
+        {
+            self.reader.reached_end = true;
+            return None;
+        }
+        self.line_count = 1;
+        if self.save_lines.keeps(lines.len()) {
             lines.push(self.reader.take_line());
         }
         if let Err(e) = self.reader.read_line() {
             return Some(Err(e));
         }
-        while !self.reader.line.starts_with('t') && !self.reader.reached_end {
-            if self.save_lines {
+        while !self.at_frame_start() && !self.reader.reached_end {
+            self.line_count += 1;
+            if self.line_count == 4 {
+                self.body_offset = self.reader.line_start_offset;
+            }
+            if self.save_lines.keeps(lines.len()) {
                 lines.push(self.reader.take_line());
             }
             if let Err(e) = self.reader.read_line() {
                 return Some(Err(e));
             }
         }
+        if self.line_count < 4 {
+            self.body_offset = self.reader.line_start_offset;
+        }
         Some(Ok((config_start, self.reader.line_start_offset, lines)))
     }
 }
 
+// `T = ...` is only a time header when reading headers case-insensitively, otherwise it is reported
+// instead of skipped over along with every configuration after it
+fn is_uppercase_time_header(line: &[u8]) -> bool {
+    let is_blank = |c: &&u8| **c == b' ' || **c == b'\t';
+    let mut rest = line.iter().skip_while(is_blank);
+    rest.next() == Some(&b'T') && rest.find(|c| !is_blank(c)) == Some(&b'=')
+}
+
+fn uppercase_header_error(offset: u64, line: &str) -> Error {
+    Error::new(
+        InvalidInput,
+        format!(
+            "Header \"{}\" at offset {} is uppercase, read with case_insensitive_headers to accept it",
+            line.trim(),
+            offset
+        ),
+    )
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum HeaderPosition {
+    #[default]
+    Leading,
+    Trailing,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ParseOptions {
+    pub case_insensitive_headers: bool,
+    pub require_frame_start: bool,
+    pub nucleotide_range: Option<(usize, usize)>,
+    pub reject_nonfinite: bool,
+    // None takes 9 or 15 from the first nucleotide line of each configuration
+    pub columns_per_nucleotide: Option<usize>,
+    // keep values after columns_per_nucleotide, or 15, in Configuration::extra_columns
+    pub extra_columns: bool,
+    pub end_offset: Option<u64>,
+    pub header_position: HeaderPosition,
+    pub validate_box: bool,
+    // read every stride-th configuration
+    pub stride: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            case_insensitive_headers: false,
+            require_frame_start: false,
+            nucleotide_range: None,
+            reject_nonfinite: false,
+            columns_per_nucleotide: None,
+            extra_columns: false,
+            end_offset: None,
+            header_position: HeaderPosition::Leading,
+            validate_box: false,
+            stride: 1,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Configuration {
     time: u64,
     cbox: Vec<f64>,
     cenergy: Vec<f64>,
     nucleotides: Vec<Vec<f64>>,
+    // values after the nucleotide columns of each nucleotide, empty unless reading extra columns
+    extra_columns: Vec<Vec<f64>>,
 }
 
 impl Configuration {
     fn get_header<'a>(
         lines: &'a [String],
         index: usize,
-        start_with: &str,
+        key: &str,
         header_type: &str,
+        options: &ParseOptions,
     ) -> Result<&'a str, Error> {
-        let line = lines.get(index).ok_or(Error::new(
-            InvalidInput,
-            format!("Missing {} header line", header_type),
-        ))?;
-        if !line.starts_with(start_with) {
-            return Err(Error::new(
-                InvalidInput,
-                format!(
-                    "line {} does not start with {}: {}",
-                    header_type, start_with, line
-                ),
-            ));
+        let missing = |line: Option<&str>| TrajectoryError::MissingHeader {
+            header: header_type.to_string(),
+            line: line.map(str::to_string),
+            location: Box::new(ErrorLocation::at_frame_line(index)),
+        };
+        let line = lines.get(index).ok_or_else(|| missing(None))?;
+        // trimming also strips the \r of CRLF line endings, for messages as well as values
+        let line = line.trim();
+        match line.split_once('=') {
+            Some((line_key, value))
+                if line_key.trim_end() == key
+                    || (options.case_insensitive_headers
+                        && line_key.trim_end().eq_ignore_ascii_case(key)) =>
+            {
+                Ok(value.trim())
+            }
+            _ => Err(missing(Some(line)).into()),
         }
-        let split = line.split('=').nth(1).ok_or(Error::new(
-            InvalidInput,
-            format!("Invalid {} header format: {}", header_type, line),
-        ))?;
-        Ok(split.trim())
     }
 
-    fn parse_values<T: std::str::FromStr>(
+    // all backends round correctly like str::parse, so they give bit-identical values
+    #[cfg(feature = "fast_float")]
+    fn parse_float(token: &str) -> Option<f64> {
+        fast_float2::parse(token).ok()
+    }
+
+    // fast_float is excluded so that enabling both only reports the compile_error
+    #[cfg(all(feature = "lexical", not(feature = "fast_float")))]
+    fn parse_float(token: &str) -> Option<f64> {
+        lexical_core::parse(token.as_bytes()).ok()
+    }
+
+    #[cfg(not(any(feature = "fast_float", feature = "lexical")))]
+    fn parse_float(token: &str) -> Option<f64> {
+        token.parse().ok()
+    }
+
+    fn parse_tokens(values: &str, name: &str) -> Result<Vec<f64>, Error> {
+        Self::parse_tokens_into(values, name, Vec::new())
+    }
+
+    fn parse_tokens_into(
         values: &str,
-        count: usize,
         name: &str,
-    ) -> Result<Vec<T>, Error> {
-        let parsed = values
-            .split(' ')
-            .map(|s| {
-                s.parse().map_err(|_| {
-                    Error::new(
-                        InvalidInput,
-                        format!("Invalid {} header value \"{}\"", name, values),
-                    )
-                })
-            })
-            .collect::<Result<Vec<T>, Error>>()?;
+        mut parsed: Vec<f64>,
+    ) -> Result<Vec<f64>, Error> {
+        for s in values.split_whitespace() {
+            parsed.push(
+                Self::parse_float(s).ok_or_else(|| TrajectoryError::BadValue {
+                    frame: None,
+                    line: format!("{} {}", name, values),
+                    value: s.to_string(),
+                    location: Box::default(),
+                })?,
+            );
+        }
+        Ok(parsed)
+    }
+
+    fn parse_values(values: &str, count: usize, name: &str) -> Result<Vec<f64>, Error> {
+        let parsed = Self::parse_tokens_into(values, name, Vec::with_capacity(count))?;
         if parsed.len() != count {
-            return Err(Error::new(
-                InvalidInput,
-                format!("Invalid {} header values \"{}\"", name, values),
-            ));
+            return Err(TrajectoryError::CountMismatch {
+                frame: None,
+                name: name.to_string(),
+                line: Some(values.to_string()),
+                expected: count,
+                found: parsed.len(),
+                location: Box::default(),
+            }
+            .into());
         }
         Ok(parsed)
     }
 
-    fn from_lines(lines: Vec<String>) -> Result<Self, Error> {
-        let time_str = Self::get_header(&lines, 0, "t", "time")?;
-        let time = time_str.parse().map_err(|_| {
-            Error::new(
-                InvalidInput,
-                format!("Invalid time header value \"{}\"", time_str),
-            )
-        })?;
+    fn parse_time(lines: &[String], options: &ParseOptions) -> Result<u64, Error> {
+        let time_str = Self::get_header(lines, 0, "t", "time", options)?;
+        time_str.parse().map_err(|_| {
+            TrajectoryError::BadValue {
+                frame: None,
+                line: format!("t = {}", time_str),
+                value: time_str.to_string(),
+                location: Box::new(ErrorLocation::at_frame_line(0)),
+            }
+            .into()
+        })
+    }
 
-        let cbox_str = Self::get_header(&lines, 1, "b", "box")?;
-        let cbox: Vec<f64> = Self::parse_values::<f64>(cbox_str, 3, "box")?;
+    fn parse_headers(lines: &[String], options: &ParseOptions) -> Result<Headers, Error> {
+        let time = Self::parse_time(lines, options)?;
 
-        let cenergy_str = Self::get_header(&lines, 2, "E", "energy")?;
-        let cenergy: Vec<f64> = Self::parse_values::<f64>(cenergy_str, 3, "energy")?;
+        let cbox_str = Self::get_header(lines, 1, "b", "box", options)?;
+        let cbox: Vec<f64> = Self::parse_values(cbox_str, 3, "box")
+            .map_err(|e| TrajectoryError::at_frame(TrajectoryError::at_frame_line(e, 1), time))?;
 
-        let nucleotides = lines
-            .into_iter()
-            .skip(3)
-            .map(|line| Self::parse_values(line.trim(), 15, "nucleotide"))
-            .collect::<Result<Vec<Vec<f64>>, Error>>()?;
+        let cenergy_str = Self::get_header(lines, 2, "E", "energy", options)?;
+        // energy token count differs between oxDNA versions and dialects, keep whatever the frame has
+        let cenergy: Vec<f64> = Self::parse_tokens(cenergy_str, "energy")
+            .map_err(|e| TrajectoryError::at_frame(TrajectoryError::at_frame_line(e, 2), time))?;
+        Ok((time, cbox, cenergy))
+    }
+
+    // position, a1, a3, velocity, angular velocity, or only the first three
+    // when momenta are not printed, e.g. with `trajectory_print_momenta = false`
+    fn parse_nucleotide(line: &str) -> Result<Vec<f64>, Error> {
+        let nucleotide = Self::parse_tokens(line.trim(), "nucleotide")?;
+        match nucleotide.len() {
+            9 | 15 => Ok(nucleotide),
+            _ => Self::parse_nucleotide_columns(line, 15),
+        }
+    }
+
+    fn detect_columns(line: &str) -> usize {
+        match line.split_whitespace().count() {
+            9 => 9,
+            _ => 15,
+        }
+    }
+
+    fn parse_nucleotide_columns(line: &str, columns: usize) -> Result<Vec<f64>, Error> {
+        Self::parse_values(line.trim(), columns, "nucleotide")
+    }
+
+    // start is the offset of the configuration in the file, for locating errors
+    pub fn from_lines(
+        lines: Vec<String>,
+        start: u64,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        Self::parse_lines(&lines, options)
+            .map_err(|e| TrajectoryError::at_frame_start(e, start, &lines, options.header_position))
+    }
+
+    fn parse_lines(lines: &[String], options: &ParseOptions) -> Result<Self, Error> {
+        let (time, cbox, cenergy) = Self::parse_headers(lines, options)?;
+        // a zero or negative edge usually means a corrupt header, and divides by zero in periodic wrapping
+        if options.validate_box {
+            if let Some(&edge) = cbox.iter().find(|edge| !(edge.is_finite() && **edge > 0.0)) {
+                return Err(Error::new(
+                    InvalidInput,
+                    format!(
+                        "Configuration at time {} has invalid box edge {}, expected finite positive values",
+                        time, edge
+                    ),
+                ));
+            }
+        }
+
+        let n_nucleotides = lines.len() - 3;
+        let (start, end) = options.nucleotide_range.unwrap_or((0, n_nucleotides));
+        if start > end {
+            return Err(Error::new(
+                InvalidInput,
+                format!("Invalid nucleotide range [{}, {})", start, end),
+            ));
+        }
+        if end > n_nucleotides {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configuration at time {} has {} nucleotides, shorter than nucleotide range end {}",
+                    time, n_nucleotides, end
+                ),
+            ));
+        }
+
+        let columns = match options.columns_per_nucleotide {
+            Some(columns) => columns,
+            None if options.extra_columns => 15,
+            None => lines.get(3).map_or(15, |line| Self::detect_columns(line)),
+        };
+        // with extra columns every line has as many values as the first, at least columns
+        let line_columns = match lines.get(3 + start) {
+            Some(line) if options.extra_columns => columns.max(line.split_whitespace().count()),
+            _ => columns,
+        };
+        // collecting into Result drops the size hint, so size from the known line count instead
+        let mut nucleotides = Vec::with_capacity(end - start);
+        let mut extra_columns = Vec::new();
+        for (frame_line, line) in lines.iter().enumerate().take(3 + end).skip(3 + start) {
+            let mut nucleotide =
+                Self::parse_nucleotide_columns(line, line_columns).map_err(|e| {
+                    TrajectoryError::at_frame(TrajectoryError::at_frame_line(e, frame_line), time)
+                })?;
+            if options.extra_columns {
+                extra_columns.push(nucleotide.split_off(columns));
+            }
+            nucleotides.push(nucleotide);
+        }
+        if options.reject_nonfinite {
+            for (index, nucleotide) in nucleotides.iter().enumerate() {
+                if let Some(column) = nucleotide.iter().position(|x| !x.is_finite()) {
+                    return Err(Error::new(
+                        InvalidInput,
+                        format!(
+                            "Configuration at time {} has non-finite value {} at nucleotide {} column {}",
+                            time,
+                            nucleotide[column],
+                            start + index,
+                            column
+                        ),
+                    ));
+                }
+            }
+        }
         Ok(Self {
             time,
             cbox,
             cenergy,
             nucleotides,
+            extra_columns,
         })
     }
 }
 
-pub fn read_confs(
-    file_path: &str,
-    offset: usize,
-    limit: usize,
-) -> Result<Vec<(usize, Configuration)>, Error> {
-    let reader = ConfigReader::new(file_path, offset, true)?;
-    let mut results = reader
-        .take(limit)
-        .enumerate()
-        .par_bridge()
-        .map(|(index, result)| match result {
-            Err(e) => (index, Err(e)),
-            Ok((_, end_offset, lines)) => match Configuration::from_lines(lines) {
-                Ok(conf) => (index, Ok((end_offset, conf))),
-                Err(e) => (index, Err(e)),
-            },
-        })
-        .collect::<Vec<_>>();
-    results.sort_by_key(|(index, _)| *index);
-    results.into_iter().map(|(_, result)| result).collect()
+// pool set by set_num_threads, None runs on the global pool with a thread per core
+static THREAD_POOL: RwLock<Option<Arc<rayon::ThreadPool>>> = RwLock::new(None);
+
+fn thread_pool() -> Option<Arc<rayon::ThreadPool>> {
+    THREAD_POOL
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
 }
 
-pub fn read_offsets(file_path: &str, offset: usize, limit: usize) -> Result<Vec<usize>, Error> {
-    let reader = ConfigReader::new(file_path, offset, false)?;
-    reader
-        .take(limit)
-        .map(|result| result.map(|(_, end_offset, _)| end_offset))
-        .collect::<Result<Vec<usize>, Error>>()
+pub fn set_thread_count(n: usize) -> Result<(), Error> {
+    let pool = match n {
+        0 => None,
+        n => Some(Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()
+                .map_err(Error::other)?,
+        )),
+    };
+    *THREAD_POOL
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = pool;
+    Ok(())
 }
 
-#[pyfunction]
-fn read_configurations<'py>(
-    py: Python<'py>,
-    file_path: &str,
-    offset: usize,
-    limit: usize,
-) -> PyResult<(
-    Vec<usize>,
-    Vec<(
-        u64,
-        Bound<'py, PyArray1<f64>>,
-        Bound<'py, PyArray1<f64>>,
-        Bound<'py, PyArray2<f64>>,
-    )>,
-)> {
-    match read_confs(file_path, offset, limit) {
-        Err(e) => {
-            if e.kind() == InvalidInput {
-                return Err(PyValueError::new_err(e.to_string()));
-            }
-            return Err(PyIOError::new_err(e.to_string()));
-        }
-        Ok(configs) => {
-            let end_offsets = configs
-                .iter()
-                .map(|(end_offset, _)| end_offset.to_owned())
-                .collect::<Vec<usize>>();
+pub fn thread_count() -> usize {
+    thread_pool().map_or_else(rayon::current_num_threads, |pool| {
+        pool.current_num_threads()
+    })
+}
 
-            let confs = configs
-                .into_iter()
-                .map(|(_, conf)| {
-                    let np_box = PyArray1::from_vec(py, conf.cbox);
-                    let np_energy = PyArray1::from_vec(py, conf.cenergy);
-                    let np_nucleotides = PyArray2::from_vec2(py, &conf.nucleotides)?;
-                    Ok((conf.time, np_box, np_energy, np_nucleotides))
-                })
-                .collect::<Result<
-                    Vec<(
-                        u64,
-                        Bound<'py, PyArray1<f64>>,
-                        Bound<'py, PyArray1<f64>>,
-                        Bound<'py, PyArray2<f64>>,
-                    )>,
-                    PyErr,
-                >>()?;
+// runs parallel iterators in f on the configured pool
+fn in_thread_pool<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    match thread_pool() {
+        Some(pool) => pool.install(f),
+        None => f(),
+    }
+}
+
+fn in_thread_pool_scope<'scope, T>(f: impl FnOnce(&rayon::Scope<'scope>) -> T) -> T {
+    match thread_pool() {
+        Some(pool) => pool.in_place_scope(f),
+        None => rayon::in_place_scope(f),
+    }
+}
+
+fn par_map_frames<I, R, T, F>(frames: I, f: F) -> Result<Vec<T>, Error>
+where
+    I: Iterator<Item = Result<R, Error>>,
+    R: Send,
+    T: Send,
+    F: Fn(R) -> Result<T, Error> + Sync,
+{
+    // frames are read on this thread and parsed by the pool, results come back in any order
+    // and are kept aside until all frames before them arrived, so the output stays in file order
+    let (sender, receiver) = mpsc::channel();
+    let mut pending = BTreeMap::new();
+    let mut results = Vec::new();
+    let mut read_error = None;
+    // the scope stays on this thread, only the parse jobs go to the pool
+    in_thread_pool_scope(|scope| {
+        let f = &f;
+        let mut frames = frames.fuse();
+        let mut spawned = 0;
+        loop {
+            if read_error.is_none() && spawned - results.len() < FRAMES_IN_FLIGHT {
+                match frames.next() {
+                    Some(Ok(frame)) => {
+                        let sender = sender.clone();
+                        let index = spawned;
+                        scope.spawn(move |_| {
+                            // the receiver is gone only when an earlier frame failed
+                            let result = f(frame).map_err(|e| TrajectoryError::at_index(e, index));
+                            let _ = sender.send((index, result));
+                        });
+                        spawned += 1;
+                        continue;
+                    }
+                    // reported once the frames before it are in, as their errors come first
+                    Some(Err(e)) => read_error = Some(e),
+                    None => {}
+                }
+            }
+            if spawned == results.len() {
+                return read_error.map_or(Ok(()), Err);
+            }
+            let (index, result) = receive_frame(&receiver);
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&results.len()) {
+                results.push(result?);
+            }
+        }
+    })?;
+    Ok(results)
+}
+
+// frames parsed at once by par_map_frames, bounds memory held besides the result
+const FRAMES_IN_FLIGHT: usize = 256;
 
-            Ok((end_offsets, confs))
+fn receive_frame<T>(receiver: &mpsc::Receiver<T>) -> T {
+    loop {
+        if let Ok(received) = receiver.try_recv() {
+            return received;
+        }
+        // a pool thread blocked here could starve the parse jobs it waits for, so it runs them meanwhile
+        match rayon::yield_now() {
+            None => {
+                return receiver
+                    .recv()
+                    .expect("a sender is kept until all frames are received")
+            }
+            Some(rayon::Yield::Executed) => {}
+            Some(rayon::Yield::Idle) => {
+                if let Ok(received) = receiver.recv_timeout(std::time::Duration::from_millis(1)) {
+                    return received;
+                }
+            }
         }
     }
 }
 
-#[pyfunction]
-fn read_indicies(file_path: &str, offset: usize, limit: usize) -> PyResult<Vec<usize>> {
-    read_offsets(file_path, offset, limit).map_err(|e| PyIOError::new_err(e.to_string()))
+// frames parsed at once when streaming, bounds memory to this many configurations
+const FRAME_CHUNK: usize = 256;
+
+// read up to FRAME_CHUNK frames on this thread and parse them in parallel, empty at end of input
+fn parse_next_chunk<I>(
+    frames: &mut I,
+    options: &ParseOptions,
+) -> Result<Vec<(u64, Configuration)>, Error>
+where
+    I: Iterator<Item = Result<(u64, u64, Vec<String>), Error>>,
+{
+    let chunk = frames
+        .take(FRAME_CHUNK)
+        .collect::<Result<Vec<_>, Error>>()?;
+    in_thread_pool(|| {
+        chunk
+            .into_par_iter()
+            .map(|(start, end_offset, lines)| {
+                Configuration::from_lines(lines, start, options).map(|conf| (end_offset, conf))
+            })
+            .collect()
+    })
 }
 
-pub fn dumps_conf(
-    time: u64,
-    box_array: numpy::ndarray::ArrayView1<'_, f64>,
-    energy_array: numpy::ndarray::ArrayView1<'_, f64>,
-    nucleotides_array: numpy::ndarray::ArrayView2<'_, f64>,
-) -> String {
-    let box_values = box_array
+type ParsedFrame = Result<(u64, Configuration), Error>;
+
+fn parse_frame(
+    (start, end_offset, lines): (u64, u64, Vec<String>),
+    options: &ParseOptions,
+) -> ParsedFrame {
+    Configuration::from_lines(lines, start, options).map(|conf| (end_offset, conf))
+}
+
+// a simulation killed while writing leaves the last configuration cut off at end of file, failing to parse
+// with a header or number cut off, or with fewer nucleotides than the configuration before it if cut off
+// between nucleotide lines. previous is the nucleotide count of the configuration before frames, if any
+fn drop_partial_frame(
+    frames: &mut Vec<ParsedFrame>,
+    previous: Option<usize>,
+    at_end: bool,
+) -> bool {
+    let Some((last, before)) = frames.split_last().filter(|_| at_end) else {
+        return false;
+    };
+    let previous = before
         .iter()
-        .map(|x| x.to_string())
+        .rev()
+        .find_map(|result| result.as_ref().ok())
+        .map(|(_, conf)| conf.nucleotides.len())
+        .or(previous);
+    let partial = match last {
+        Ok((_, conf)) => previous.is_some_and(|previous| conf.nucleotides.len() < previous),
+        Err(_) => true,
+    };
+    if partial {
+        frames.pop();
+    }
+    partial
+}
+
+// energy token count of every frame, None if there are none or their counts differ
+fn uniform_energy_count(configs: &[(u64, Configuration)]) -> Option<usize> {
+    let (_, first) = configs.first()?;
+    configs
+        .iter()
+        .all(|(_, conf)| conf.cenergy.len() == first.cenergy.len())
+        .then_some(first.cenergy.len())
+}
+
+// energy token count differs between oxDNA versions, but not between frames of one file,
+// so frames must match the first one unless a count is given
+fn check_energy_counts(
+    configs: &[(u64, Configuration)],
+    expected: Option<usize>,
+) -> Result<Option<usize>, Error> {
+    let Some(expected) = expected.or_else(|| configs.first().map(|(_, conf)| conf.cenergy.len()))
+    else {
+        return Ok(None);
+    };
+    for (index, (_, conf)) in configs.iter().enumerate() {
+        if conf.cenergy.len() != expected {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configuration {} at time {} has {} energy values, expected {}",
+                    index,
+                    conf.time,
+                    conf.cenergy.len(),
+                    expected
+                ),
+            ));
+        }
+    }
+    Ok(Some(expected))
+}
+
+// element type of returned nucleotide arrays, values are always parsed as f64 then narrowed
+pub trait NucleotideFloat: numpy::Element + Copy + Send {
+    fn from_f64(value: f64) -> Self;
+}
+
+impl NucleotideFloat for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+impl NucleotideFloat for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+pub type StackedConfigurations<T = f64> = (
+    Vec<u64>,
+    numpy::ndarray::Array2<f64>,
+    numpy::ndarray::Array2<f64>,
+    numpy::ndarray::Array3<T>,
+    numpy::ndarray::Array3<T>,
+);
+
+// times, boxes, energies and nucleotides of all configurations in one array each,
+// every configuration must have as many nucleotides and energy values as the first
+pub fn stack_confs<T: NucleotideFloat>(
+    configs: Vec<Configuration>,
+    columns: usize,
+) -> Result<StackedConfigurations<T>, Error> {
+    let n_frames = configs.len();
+    let (n_nucleotides, n_columns, n_energies) = configs.first().map_or((0, columns, 0), |conf| {
+        (
+            conf.nucleotides.len(),
+            conf.nucleotides.first().map_or(columns, Vec::len),
+            conf.cenergy.len(),
+        )
+    });
+    let mut times = Vec::with_capacity(n_frames);
+    let mut boxes = Vec::with_capacity(n_frames * 3);
+    let mut energies = Vec::with_capacity(n_frames * n_energies);
+    let mut nucleotides = Vec::with_capacity(n_frames * n_nucleotides * n_columns);
+    let extra_width = |conf: &Configuration| conf.extra_columns.first().map_or(0, Vec::len);
+    let n_extra_columns = configs.first().map_or(0, extra_width);
+    let mut extra_columns = Vec::with_capacity(n_frames * n_nucleotides * n_extra_columns);
+    for (index, conf) in configs.into_iter().enumerate() {
+        if extra_width(&conf) != n_extra_columns {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configuration {} at time {} has {} extra columns, expected {} to stack configurations",
+                    index,
+                    conf.time,
+                    extra_width(&conf),
+                    n_extra_columns
+                ),
+            ));
+        }
+        let conf_columns = conf.nucleotides.first().map_or(n_columns, Vec::len);
+        if conf.nucleotides.len() != n_nucleotides
+            || conf_columns != n_columns
+            || conf.cenergy.len() != n_energies
+        {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configuration {} at time {} has {} nucleotides of {} columns and {} energy values, \
+                     expected {}, {} and {} to stack configurations",
+                    index,
+                    conf.time,
+                    conf.nucleotides.len(),
+                    conf_columns,
+                    conf.cenergy.len(),
+                    n_nucleotides,
+                    n_columns,
+                    n_energies
+                ),
+            ));
+        }
+        times.push(conf.time);
+        boxes.extend(conf.cbox);
+        energies.extend(conf.cenergy);
+        for nucleotide in conf.nucleotides {
+            nucleotides.extend(nucleotide.into_iter().map(T::from_f64));
+        }
+        for extra in conf.extra_columns {
+            extra_columns.extend(extra.into_iter().map(T::from_f64));
+        }
+    }
+    let boxes = numpy::ndarray::Array2::from_shape_vec((n_frames, 3), boxes)
+        .map_err(|e| Error::new(InvalidInput, e.to_string()))?;
+    let energies = numpy::ndarray::Array2::from_shape_vec((n_frames, n_energies), energies)
+        .expect("energy counts are checked to match");
+    let nucleotides =
+        numpy::ndarray::Array3::from_shape_vec((n_frames, n_nucleotides, n_columns), nucleotides)
+            .map_err(|e| Error::new(InvalidInput, e.to_string()))?;
+    let extra_columns = numpy::ndarray::Array3::from_shape_vec(
+        (n_frames, n_nucleotides, n_extra_columns),
+        extra_columns,
+    )
+    .map_err(|e| Error::new(InvalidInput, e.to_string()))?;
+    Ok((times, boxes, energies, nucleotides, extra_columns))
+}
+
+pub fn read_confs(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<(u64, Configuration)>, TrajectoryError> {
+    let mut reader = ConfigReader::new(file_path, offset, SaveLines::All, options)?;
+    Ok(read_confs_from(&mut reader, limit, options)?)
+}
+
+fn read_confs_from<R: BufRead + Send>(
+    reader: &mut ConfigReader<R>,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<(u64, Configuration)>, Error> {
+    par_map_frames(reader.by_ref().take(limit), |(start, end_offset, lines)| {
+        Configuration::from_lines(lines, start, options).map(|conf| (end_offset, conf))
+    })
+    .map_err(|e| reader.with_line_number(e))
+}
+
+// like read_confs_from, but a partial last configuration is dropped instead of failing the read
+fn read_confs_dropping_partial<R: BufRead + Send>(
+    reader: &mut ConfigReader<R>,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<(Vec<(u64, Configuration)>, bool), Error> {
+    let mut frames = par_map_frames(reader.by_ref().take(limit), |frame| {
+        Ok(parse_frame(frame, options))
+    })?;
+    let discarded = drop_partial_frame(&mut frames, None, reader.at_end_of_input());
+    let configs = frames
+        .into_iter()
+        .enumerate()
+        .map(|(index, result)| result.map_err(|e| TrajectoryError::at_index(e, index)))
+        .collect::<Result<Vec<_>, Error>>()
+        .map_err(|e| reader.with_line_number(e))?;
+    Ok((configs, discarded))
+}
+
+pub fn read_confs_stdin(
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<(u64, Configuration)>, Error> {
+    // stdin is not seekable, read from wherever it currently is
+    let reader = LineReader::from_reader(BufReader::new(std::io::stdin()), 0);
+    let reader = ConfigReader::from_reader(reader, SaveLines::All, options);
+    par_map_frames(reader.take(limit), |(start, end_offset, lines)| {
+        Configuration::from_lines(lines, start, options).map(|conf| (end_offset, conf))
+    })
+}
+
+pub fn read_confs_multi(
+    file_paths: &[String],
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<(usize, u64, Configuration)>, Error> {
+    let frames = file_paths
+        .iter()
+        .enumerate()
+        .flat_map(|(file_index, file_path)| {
+            let reader: Box<dyn Iterator<Item = _> + Send> =
+                match ConfigReader::new(file_path, 0, SaveLines::All, options) {
+                    Ok(reader) => Box::new(reader),
+                    Err(e) => Box::new(std::iter::once(Err(e))),
+                };
+            reader.map(move |result| result.map(|frame| (file_index, frame)))
+        })
+        .take(limit);
+    par_map_frames(frames, |(file_index, (start, end_offset, lines))| {
+        Configuration::from_lines(lines, start, options)
+            .map(|conf| (file_index, end_offset, conf))
+            .map_err(|e| TrajectoryError::with_line_number(e, &file_paths[file_index]))
+    })
+}
+
+pub fn read_confs_multi_parallel(
+    specs: &[(String, u64, usize)],
+    options: &ParseOptions,
+) -> Result<Vec<(usize, u64, Configuration)>, Error> {
+    let per_file = in_thread_pool(|| {
+        specs
+            .par_iter()
+            .map(|(file_path, offset, limit)| read_confs(file_path, *offset, *limit, options))
+            .collect::<Result<Vec<_>, TrajectoryError>>()
+    })?;
+    Ok(per_file
+        .into_iter()
+        .enumerate()
+        .flat_map(|(file_index, configs)| {
+            configs
+                .into_iter()
+                .map(move |(end_offset, conf)| (file_index, end_offset, conf))
+        })
+        .collect())
+}
+
+// each worker opens its own file at the first start offset of its chunk, so reading lines
+// is not limited to a single sequential reader
+pub fn read_confs_parallel(
+    file_path: &str,
+    offsets: &[u64],
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<(u64, Configuration)>, Error> {
+    let offsets = &offsets[..limit.min(offsets.len())];
+    let options = ParseOptions {
+        require_frame_start: true,
+        ..*options
+    };
+    let per_chunk = in_thread_pool(|| {
+        let chunk_size = offsets
+            .len()
+            .div_ceil(rayon::current_num_threads() * 4)
+            .max(1);
+        offsets
+            .par_chunks(chunk_size)
+            .enumerate()
+            .map(|(chunk_index, chunk)| {
+                let mut reader = ConfigReader::new(file_path, chunk[0], SaveLines::All, &options)?;
+                let mut next_offset = chunk[0];
+                let mut configs = Vec::with_capacity(chunk.len());
+                for (index, &offset) in chunk.iter().enumerate() {
+                    // offsets skipping configurations move this worker's reader instead
+                    if offset != next_offset {
+                        reader = ConfigReader::new(file_path, offset, SaveLines::All, &options)?;
+                    }
+                    let Some(frame) = reader.next() else {
+                        return Err(Error::new(
+                            InvalidInput,
+                            format!(
+                                "No configuration at offset {}, it is at end of file",
+                                offset
+                            ),
+                        ));
+                    };
+                    let (start, end_offset, lines) = frame?;
+                    next_offset = end_offset;
+                    let conf = Configuration::from_lines(lines, start, &options).map_err(|e| {
+                        let e = TrajectoryError::at_index(e, chunk_index * chunk_size + index);
+                        reader.with_line_number(e)
+                    })?;
+                    configs.push((end_offset, conf));
+                }
+                Ok(configs)
+            })
+            .collect::<Result<Vec<_>, Error>>()
+    })?;
+    Ok(per_chunk.into_iter().flatten().collect())
+}
+
+// start offsets come from the sidecar index or a scan up to the last requested configuration
+// that keeps no lines, then only the requested configurations are parsed, in parallel
+pub fn read_confs_at_indices(
+    file_path: &str,
+    indices: &[usize],
+    options: &ParseOptions,
+) -> Result<Vec<(u64, Configuration)>, Error> {
+    let Some(&last_index) = indices.iter().max() else {
+        return Ok(Vec::new());
+    };
+    let starts = match load_frame_index(file_path, options)? {
+        Some(entries) => entries.iter().map(|entry| entry.start).collect(),
+        None => ConfigReader::new(file_path, 0, SaveLines::None, options)?
+            .take(last_index + 1)
+            .map(|result| result.map(|(config_start, _, _)| config_start))
+            .collect::<Result<Vec<u64>, Error>>()?,
+    };
+    if last_index >= starts.len() {
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "Configuration index {} is out of range for {} configurations",
+                last_index,
+                starts.len()
+            ),
+        ));
+    }
+    let offsets = indices
+        .iter()
+        .map(|&index| starts[index])
+        .collect::<Vec<u64>>();
+    read_confs_parallel(file_path, &offsets, offsets.len(), options)
+}
+
+pub fn read_confs_by_index(
+    file_path: &str,
+    starts: &[u64],
+    start_index: usize,
+    count: usize,
+    options: &ParseOptions,
+) -> Result<Vec<(u64, Configuration)>, Error> {
+    if start_index.saturating_add(count) > starts.len() {
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "Configurations [{}, {}) are out of bounds for {} offsets",
+                start_index,
+                start_index.saturating_add(count),
+                starts.len()
+            ),
+        ));
+    }
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    Ok(read_confs(file_path, starts[start_index], count, options)?)
+}
+
+// bytes read at a time when scanning backward from end of file
+const BACKWARD_BLOCK: usize = 1 << 16;
+
+// start offset of the n-th last configuration, found by scanning line starts backward from end of file,
+// the start of the file if it has fewer configurations. A `t =` line only starts a configuration with
+// leading headers, so trailing ones are rejected
+fn find_last_frames_start(file_path: &str, n: usize, options: &ParseOptions) -> Result<u64, Error> {
+    if options.header_position != HeaderPosition::Leading {
+        return Err(Error::new(
+            InvalidInput,
+            "Reading the last configurations requires headers leading each configuration",
+        ));
+    }
+    let is_frame_start = |line: &[u8]| {
+        let line = &line[line
+            .iter()
+            .take_while(|&&c| c == b' ' || c == b'\t')
+            .count()..];
+        match line.first() {
+            Some(b't') => true,
+            Some(b'T') => options.case_insensitive_headers,
+            _ => false,
+        }
+    };
+    let mut file = open_uncompressed(file_path)?;
+    let mut position = file.metadata()?.len();
+    // start of the previously read block, enough to tell whether a line starting there is a configuration start
+    let mut carry = Vec::new();
+    let mut found = 0;
+    while position > 0 && n > 0 {
+        let size = BACKWARD_BLOCK.min(position as usize);
+        position -= size as u64;
+        let mut block = vec![0u8; size];
+        file.seek(SeekFrom::Start(position))?;
+        file.read_exact(&mut block)?;
+        let next_carry = block[..size.min(256)].to_vec();
+        block.extend_from_slice(&carry);
+        carry = next_carry;
+        // index 0 is a line start only at start of file, otherwise it is checked with the next block
+        for index in (0..=size).rev() {
+            let line_start = match index {
+                0 => position == 0,
+                _ => block[index - 1] == b'\n',
+            };
+            if line_start && is_frame_start(&block[index..]) {
+                found += 1;
+                if found == n {
+                    return Ok(position + index as u64);
+                }
+            }
+        }
+    }
+    Ok(0)
+}
+
+pub fn read_last_confs(
+    file_path: &str,
+    n: usize,
+    options: &ParseOptions,
+) -> Result<Vec<(u64, Configuration)>, Error> {
+    let start = find_last_frames_start(file_path, n, options)?;
+    Ok(read_confs(file_path, start, n, options)?)
+}
+
+fn map_frame_lines<T, F>(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+    f: F,
+) -> Result<Vec<T>, Error>
+where
+    T: Send,
+    F: Fn(Vec<String>) -> Result<T, Error> + Sync + Send,
+{
+    let reader = ConfigReader::new(file_path, offset, SaveLines::All, options)?;
+    par_map_frames(reader.take(limit), |(_, _, lines)| f(lines))
+}
+
+fn check_masses(masses: Option<&[f64]>, n_nucleotides: usize, time: u64) -> Result<(), Error> {
+    match masses {
+        Some(masses) if masses.len() != n_nucleotides => Err(Error::new(
+            InvalidInput,
+            format!(
+                "Got {} masses for configuration at time {} with {} nucleotides",
+                masses.len(),
+                time,
+                n_nucleotides
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+pub fn read_centers_of_mass(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    masses: Option<&[f64]>,
+) -> Result<Vec<(u64, [f64; 3])>, Error> {
+    let options = ParseOptions::default();
+    map_frame_lines(file_path, offset, limit, &options, |lines| {
+        let (time, _, _) = Configuration::parse_headers(&lines, &options)?;
+        check_masses(masses, lines.len() - 3, time)?;
+        let mut weighted = [0.0; 3];
+        let mut total_mass = 0.0;
+        for (index, line) in lines[3..].iter().enumerate() {
+            let nucleotide = Configuration::parse_nucleotide(line)?;
+            let mass = masses.map_or(1.0, |masses| masses[index]);
+            for axis in 0..3 {
+                weighted[axis] += mass * nucleotide[axis];
+            }
+            total_mass += mass;
+        }
+        Ok((time, weighted.map(|value| value / total_mass)))
+    })
+}
+
+// minimum and maximum position
+type Bounds = [[f64; 3]; 2];
+
+pub fn read_bounds(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+) -> Result<Vec<(u64, Bounds)>, Error> {
+    let options = ParseOptions::default();
+    map_frame_lines(file_path, offset, limit, &options, |lines| {
+        let (time, _, _) = Configuration::parse_headers(&lines, &options)?;
+        let mut bounds = [[f64::INFINITY; 3], [f64::NEG_INFINITY; 3]];
+        for line in &lines[3..] {
+            let nucleotide = Configuration::parse_nucleotide(line)?;
+            for axis in 0..3 {
+                bounds[0][axis] = bounds[0][axis].min(nucleotide[axis]);
+                bounds[1][axis] = bounds[1][axis].max(nucleotide[axis]);
+            }
+        }
+        Ok((time, bounds))
+    })
+}
+
+type Matrix3 = [[f64; 3]; 3];
+
+// eigenvalues and unit eigenvectors as rows of a symmetric 3x3 matrix by cyclic Jacobi rotations
+fn symmetric_eigen(mut m: Matrix3) -> ([f64; 3], Matrix3) {
+    let mut vectors = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for _ in 0..50 {
+        let off_diagonal = m[0][1] * m[0][1] + m[0][2] * m[0][2] + m[1][2] * m[1][2];
+        if off_diagonal
+            <= f64::EPSILON
+                * f64::EPSILON
+                * (m[0][0] * m[0][0] + m[1][1] * m[1][1] + m[2][2] * m[2][2])
+        {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if m[p][q] == 0.0 {
+                continue;
+            }
+            let theta = (m[q][q] - m[p][p]) / (2.0 * m[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+            for row in m.iter_mut() {
+                let (mp, mq) = (row[p], row[q]);
+                row[p] = c * mp - s * mq;
+                row[q] = s * mp + c * mq;
+            }
+            let (mp, mq) = (m[p], m[q]);
+            for k in 0..3 {
+                m[p][k] = c * mp[k] - s * mq[k];
+                m[q][k] = s * mp[k] + c * mq[k];
+            }
+            let (vp, vq) = (vectors[p], vectors[q]);
+            for k in 0..3 {
+                vectors[p][k] = c * vp[k] - s * vq[k];
+                vectors[q][k] = s * vp[k] + c * vq[k];
+            }
+        }
+    }
+    ([m[0][0], m[1][1], m[2][2]], vectors)
+}
+
+pub fn read_oriented_bounds(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+) -> Result<Vec<([f64; 3], Matrix3)>, Error> {
+    let options = ParseOptions::default();
+    map_frame_lines(file_path, offset, limit, &options, |lines| {
+        Configuration::parse_headers(&lines, &options)?;
+        let positions = lines[3..]
+            .iter()
+            .map(|line| Configuration::parse_nucleotide(line).map(|n| [n[0], n[1], n[2]]))
+            .collect::<Result<Vec<[f64; 3]>, Error>>()?;
+        let n_nucleotides = positions.len() as f64;
+        let mut mean = [0.0; 3];
+        for position in &positions {
+            for axis in 0..3 {
+                mean[axis] += position[axis] / n_nucleotides;
+            }
+        }
+        let mut covariance = [[0.0; 3]; 3];
+        for position in &positions {
+            for i in 0..3 {
+                for j in 0..3 {
+                    covariance[i][j] += (position[i] - mean[i]) * (position[j] - mean[j]);
+                }
+            }
+        }
+        let (values, vectors) = symmetric_eigen(covariance);
+        // principal axes by decreasing spread, as a proper rotation
+        let mut order = [0, 1, 2];
+        order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+        let mut rotation = order.map(|axis| vectors[axis]);
+        let [u, v, _] = rotation;
+        rotation[2] = [
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ];
+        let mut extents = [0.0; 3];
+        for (axis, extent) in extents.iter_mut().enumerate() {
+            let projected = positions
+                .iter()
+                .map(|position| (0..3).map(|k| rotation[axis][k] * position[k]).sum::<f64>());
+            let (min, max) = projected.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), x| {
+                (min.min(x), max.max(x))
+            });
+            *extent = if positions.is_empty() { 0.0 } else { max - min };
+        }
+        Ok((extents, rotation))
+    })
+}
+
+pub fn read_strand_centers(
+    file_path: &str,
+    strand_lengths: &[usize],
+    offset: u64,
+    limit: usize,
+) -> Result<Vec<Vec<[f64; 3]>>, Error> {
+    let n_strand_nucleotides = strand_lengths.iter().sum::<usize>();
+    let options = ParseOptions::default();
+    map_frame_lines(file_path, offset, limit, &options, |lines| {
+        let (time, _, _) = Configuration::parse_headers(&lines, &options)?;
+        if lines.len() - 3 != n_strand_nucleotides {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configuration at time {} has {} nucleotides, strands have {}",
+                    time,
+                    lines.len() - 3,
+                    n_strand_nucleotides
+                ),
+            ));
+        }
+        let mut nucleotide_lines = lines[3..].iter();
+        strand_lengths
+            .iter()
+            .map(|&length| {
+                let mut sum = [0.0; 3];
+                for line in nucleotide_lines.by_ref().take(length) {
+                    let nucleotide = Configuration::parse_nucleotide(line)?;
+                    for axis in 0..3 {
+                        sum[axis] += nucleotide[axis];
+                    }
+                }
+                Ok(sum.map(|value| value / length as f64))
+            })
+            .collect()
+    })
+}
+
+pub fn read_rmsds(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    reference: &[[f64; 3]],
+) -> Result<Vec<(u64, f64)>, Error> {
+    let options = ParseOptions::default();
+    map_frame_lines(file_path, offset, limit, &options, |lines| {
+        let (time, _, _) = Configuration::parse_headers(&lines, &options)?;
+        let n_nucleotides = lines.len() - 3;
+        if n_nucleotides != reference.len() {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configuration at time {} has {} nucleotides, reference has {}",
+                    time,
+                    n_nucleotides,
+                    reference.len()
+                ),
+            ));
+        }
+        let mut squared = 0.0;
+        for (line, position) in lines[3..].iter().zip(reference) {
+            let nucleotide = Configuration::parse_nucleotide(line)?;
+            squared += (0..3)
+                .map(|axis| (nucleotide[axis] - position[axis]).powi(2))
+                .sum::<f64>();
+        }
+        Ok((time, (squared / n_nucleotides as f64).sqrt()))
+    })
+}
+
+pub fn read_end_to_end_vectors(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    first: usize,
+    last: usize,
+) -> Result<Vec<(u64, [f64; 3])>, Error> {
+    let options = ParseOptions::default();
+    map_frame_lines(file_path, offset, limit, &options, |lines| {
+        let (time, cbox, _) = Configuration::parse_headers(&lines, &options)?;
+        let n_nucleotides = lines.len() - 3;
+        if first >= n_nucleotides || last >= n_nucleotides {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configuration at time {} has {} nucleotides, no nucleotide {}",
+                    time,
+                    n_nucleotides,
+                    first.max(last)
+                ),
+            ));
+        }
+        // the minimum image divides by box edges
+        if let Some(&edge) = cbox.iter().find(|edge| !(edge.is_finite() && **edge > 0.0)) {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configuration at time {} has invalid box edge {}, no nearest periodic image",
+                    time, edge
+                ),
+            ));
+        }
+        let start = Configuration::parse_nucleotide(&lines[3 + first])?;
+        let end = Configuration::parse_nucleotide(&lines[3 + last])?;
+        let mut vector = [0.0; 3];
+        for axis in 0..3 {
+            let delta = end[axis] - start[axis];
+            // minimum image convention
+            vector[axis] = delta - cbox[axis] * (delta / cbox[axis]).round();
+        }
+        let norm = vector.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configuration at time {} has nucleotides {} and {} at the same position, no end-to-end direction",
+                    time, first, last
+                ),
+            ));
+        }
+        Ok((time, vector.map(|x| x / norm)))
+    })
+}
+
+fn minimum_image_distance_squared(a: &[f64; 3], b: &[f64; 3], cbox: &[f64]) -> f64 {
+    (0..3)
+        .map(|axis| {
+            let delta = b[axis] - a[axis];
+            (delta - cbox[axis] * (delta / cbox[axis]).round()).powi(2)
+        })
+        .sum()
+}
+
+// displacement of each nucleotide from one configuration to the next, frames are parsed in parallel
+// and differenced in order afterwards
+pub fn read_displacement_rows(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    minimum_image: bool,
+) -> Result<(usize, Vec<Vec<f64>>), Error> {
+    let options = ParseOptions::default();
+    let frames = map_frame_lines(file_path, offset, limit, &options, |lines| {
+        let (time, cbox, _) = Configuration::parse_headers(&lines, &options)?;
+        let positions = lines[3..]
+            .iter()
+            .map(|line| {
+                let nucleotide = Configuration::parse_nucleotide(line)?;
+                Ok([nucleotide[0], nucleotide[1], nucleotide[2]])
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok((time, cbox, positions))
+    })?;
+    let n_nucleotides = frames
+        .first()
+        .map_or(0, |(_, _, positions)| positions.len());
+    let rows = frames
+        .windows(2)
+        .map(|pair| {
+            let ((_, _, previous), (time, cbox, current)) = (&pair[0], &pair[1]);
+            if current.len() != previous.len() {
+                return Err(Error::new(
+                    InvalidInput,
+                    format!(
+                        "Configuration at time {} has {} nucleotides, previous configuration has {}",
+                        time,
+                        current.len(),
+                        previous.len()
+                    ),
+                ));
+            }
+            Ok(previous
+                .iter()
+                .zip(current)
+                .map(|(a, b)| {
+                    if minimum_image {
+                        minimum_image_distance_squared(a, b, cbox).sqrt()
+                    } else {
+                        (0..3).map(|axis| (b[axis] - a[axis]).powi(2)).sum::<f64>().sqrt()
+                    }
+                })
+                .collect())
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok((n_nucleotides, rows))
+}
+
+// only nucleotides appearing in a pair are parsed, each once per configuration
+pub fn read_pair_distance_rows(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    pairs: &[(usize, usize)],
+) -> Result<Vec<Vec<f64>>, Error> {
+    let mut needed = pairs
+        .iter()
+        .flat_map(|&(i, j)| [i, j])
+        .collect::<Vec<usize>>();
+    needed.sort_unstable();
+    needed.dedup();
+    let options = ParseOptions::default();
+    map_frame_lines(file_path, offset, limit, &options, |lines| {
+        let (time, cbox, _) = Configuration::parse_headers(&lines, &options)?;
+        let n_nucleotides = lines.len() - 3;
+        if let Some(&index) = needed.last().filter(|&&index| index >= n_nucleotides) {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configuration at time {} has {} nucleotides, no nucleotide {}",
+                    time, n_nucleotides, index
+                ),
+            ));
+        }
+        let positions = needed
+            .iter()
+            .map(|&index| {
+                let nucleotide = Configuration::parse_nucleotide(&lines[3 + index])?;
+                Ok([nucleotide[0], nucleotide[1], nucleotide[2]])
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let position = |index| &positions[needed.binary_search(&index).expect("index is needed")];
+        Ok(pairs
+            .iter()
+            .map(|&(i, j)| minimum_image_distance_squared(position(i), position(j), &cbox).sqrt())
+            .collect())
+    })
+}
+
+// below this many nucleotides checking every pair is faster than building cells
+const CELL_LIST_MIN_NUCLEOTIDES: usize = 64;
+
+pub fn count_clashes(positions: &[[f64; 3]], cbox: &[f64], radius: f64) -> u64 {
+    let radius_squared = radius * radius;
+    // about one nucleotide per cell, so a small radius in a large box does not allocate more cells than nucleotides
+    let max_cells = (positions.len() as f64).cbrt().ceil() as usize;
+    let n_cells = [0, 1, 2].map(|axis| ((cbox[axis] / radius).floor() as usize).min(max_cells));
+    // cells at least `radius` wide, 3 per axis so the 27 neighbors of a cell are distinct cells
+    if positions.len() < CELL_LIST_MIN_NUCLEOTIDES || n_cells.iter().any(|&n| n < 3) {
+        let mut count = 0;
+        for (i, a) in positions.iter().enumerate() {
+            for b in &positions[i + 1..] {
+                if minimum_image_distance_squared(a, b, cbox) < radius_squared {
+                    count += 1;
+                }
+            }
+        }
+        return count;
+    }
+    let cell_of = |position: &[f64; 3]| {
+        [0, 1, 2].map(|axis| {
+            let fraction = (position[axis] / cbox[axis]).rem_euclid(1.0);
+            ((fraction * n_cells[axis] as f64) as usize).min(n_cells[axis] - 1)
+        })
+    };
+    let cell_index = |cell: [usize; 3]| (cell[0] * n_cells[1] + cell[1]) * n_cells[2] + cell[2];
+    let mut cells = vec![Vec::new(); n_cells.iter().product()];
+    for (index, position) in positions.iter().enumerate() {
+        cells[cell_index(cell_of(position))].push(index);
+    }
+    let mut count = 0;
+    for (i, a) in positions.iter().enumerate() {
+        let cell = cell_of(a);
+        for offset in 0..27 {
+            let steps = [offset / 9, offset / 3 % 3, offset % 3];
+            let neighbor = [0, 1, 2]
+                .map(|axis| (cell[axis] + n_cells[axis] + steps[axis] - 1) % n_cells[axis]);
+            for &j in &cells[cell_index(neighbor)] {
+                // each pair is found from both of its cells, count it from the lower index
+                if i < j && minimum_image_distance_squared(a, &positions[j], cbox) < radius_squared
+                {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+pub fn read_clash_counts(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    radius: f64,
+) -> Result<Vec<(u64, u64)>, Error> {
+    if radius.is_nan() || radius <= 0.0 {
+        return Err(Error::new(
+            InvalidInput,
+            format!("Clash radius must be positive, got {}", radius),
+        ));
+    }
+    let options = ParseOptions::default();
+    map_frame_lines(file_path, offset, limit, &options, |lines| {
+        let (time, cbox, _) = Configuration::parse_headers(&lines, &options)?;
+        let positions = lines[3..]
+            .iter()
+            .map(|line| {
+                let nucleotide = Configuration::parse_nucleotide(line)?;
+                Ok([nucleotide[0], nucleotide[1], nucleotide[2]])
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok((time, count_clashes(&positions, &cbox, radius)))
+    })
+}
+
+// Welford's running mean and sum of squared deviations of each position component
+#[derive(Default)]
+struct PositionStatistics {
+    count: u64,
+    mean: Vec<[f64; 3]>,
+    m2: Vec<[f64; 3]>,
+}
+
+impl PositionStatistics {
+    fn add(
+        mut self,
+        (start, _, lines): (u64, u64, Vec<String>),
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        // a frame cut off within its headers has fewer than 3 lines
+        let (time, _, _) = Configuration::parse_headers(&lines, options).map_err(|e| {
+            TrajectoryError::at_frame_start(e, start, &lines, options.header_position)
+        })?;
+        let n_nucleotides = lines.len() - 3;
+        if self.count == 0 {
+            self.mean = vec![[0.0; 3]; n_nucleotides];
+            self.m2 = vec![[0.0; 3]; n_nucleotides];
+        } else if n_nucleotides != self.mean.len() {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configuration at time {} has {} nucleotides, expected {}",
+                    time,
+                    n_nucleotides,
+                    self.mean.len()
+                ),
+            ));
+        }
+        self.count += 1;
+        for ((line, mean), m2) in lines[3..].iter().zip(&mut self.mean).zip(&mut self.m2) {
+            let nucleotide = Configuration::parse_nucleotide(line)?;
+            for axis in 0..3 {
+                let delta = nucleotide[axis] - mean[axis];
+                mean[axis] += delta / self.count as f64;
+                m2[axis] += delta * (nucleotide[axis] - mean[axis]);
+            }
+        }
+        Ok(self)
+    }
+
+    // Chan et al. pairwise update, so frames can be accumulated on separate threads
+    fn merge(self, other: Self) -> Result<Self, Error> {
+        if self.count == 0 {
+            return Ok(other);
+        }
+        if other.count == 0 {
+            return Ok(self);
+        }
+        if self.mean.len() != other.mean.len() {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configurations have different nucleotide counts {} and {}",
+                    self.mean.len(),
+                    other.mean.len()
+                ),
+            ));
+        }
+        let count = self.count + other.count;
+        let weight = other.count as f64 / count as f64;
+        let mut merged = self;
+        for (((mean, m2), other_mean), other_m2) in merged
+            .mean
+            .iter_mut()
+            .zip(&mut merged.m2)
+            .zip(&other.mean)
+            .zip(&other.m2)
+        {
+            for axis in 0..3 {
+                let delta = other_mean[axis] - mean[axis];
+                mean[axis] += delta * weight;
+                m2[axis] += other_m2[axis] + delta * delta * merged.count as f64 * weight;
+            }
+        }
+        merged.count = count;
+        Ok(merged)
+    }
+}
+
+type MeanVariance = (Vec<[f64; 3]>, Vec<[f64; 3]>);
+
+// single pass over frames, memory does not grow with trajectory length
+pub fn read_position_mean_variance(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+) -> Result<MeanVariance, Error> {
+    let options = ParseOptions::default();
+    let reader = ConfigReader::new(file_path, offset, SaveLines::All, &options)?;
+    let statistics = in_thread_pool(|| {
+        reader
+            .take(limit)
+            .par_bridge()
+            .try_fold(PositionStatistics::default, |statistics, frame| {
+                statistics.add(frame?, &options)
+            })
+            .try_reduce(PositionStatistics::default, PositionStatistics::merge)
+    })?;
+    let count = statistics.count as f64;
+    let variance = statistics
+        .m2
+        .iter()
+        .map(|m2| m2.map(|x| x / count))
+        .collect();
+    Ok((statistics.mean, variance))
+}
+
+pub fn read_temperatures(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    masses: Option<&[f64]>,
+) -> Result<Vec<(u64, f64)>, Error> {
+    let options = ParseOptions::default();
+    map_frame_lines(file_path, offset, limit, &options, |lines| {
+        let (time, _, _) = Configuration::parse_headers(&lines, &options)?;
+        let n_nucleotides = lines.len() - 3;
+        check_masses(masses, n_nucleotides, time)?;
+        let mut kinetic = 0.0;
+        for (index, line) in lines[3..].iter().enumerate() {
+            // needs velocity columns
+            let nucleotide = Configuration::parse_nucleotide_columns(line, 15)?;
+            let mass = masses.map_or(1.0, |masses| masses[index]);
+            // unit moment of inertia, as in oxDNA
+            kinetic += mass * nucleotide[9..12].iter().map(|v| v * v).sum::<f64>();
+            kinetic += nucleotide[12..15].iter().map(|l| l * l).sum::<f64>();
+        }
+        // equipartition over 3 translational and 3 rotational degrees of freedom, k_B = 1
+        Ok((time, kinetic / (6 * n_nucleotides) as f64))
+    })
+}
+
+pub fn read_p2_order(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    axis: [f64; 3],
+) -> Result<Vec<f64>, Error> {
+    let axis_norm = axis.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if axis_norm == 0.0 || !axis_norm.is_finite() {
+        return Err(Error::new(InvalidInput, format!("Invalid axis {:?}", axis)));
+    }
+    let axis = axis.map(|x| x / axis_norm);
+    let options = ParseOptions::default();
+    map_frame_lines(file_path, offset, limit, &options, |lines| {
+        Configuration::parse_headers(&lines, &options)?;
+        let mut p2_sum = 0.0;
+        for line in &lines[3..] {
+            let nucleotide = Configuration::parse_nucleotide(line)?;
+            let a1 = &nucleotide[3..6];
+            let a1_norm = a1.iter().map(|x| x * x).sum::<f64>().sqrt();
+            let cos = a1.iter().zip(axis).map(|(a, b)| a * b).sum::<f64>() / a1_norm;
+            p2_sum += 1.5 * cos * cos - 0.5;
+        }
+        Ok(p2_sum / (lines.len() - 3) as f64)
+    })
+}
+
+pub fn read_energy_mismatches(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    tolerance: f64,
+) -> Result<Vec<usize>, Error> {
+    let options = ParseOptions::default();
+    let reader = ConfigReader::new(file_path, offset, SaveLines::Headers, &options)?;
+    let mut mismatched = Vec::new();
+    for (frame, result) in reader.take(limit).enumerate() {
+        let (_, _, lines) = result?;
+        let (time, _, energy) = Configuration::parse_headers(&lines, &options)?;
+        let [total, potential, kinetic] = energy[..] else {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Configuration at time {} has {} energy values, expected total, potential and kinetic",
+                    time,
+                    energy.len()
+                ),
+            ));
+        };
+        let difference = (total - (potential + kinetic)).abs();
+        if difference.is_nan() || difference > tolerance {
+            mismatched.push(frame);
+        }
+    }
+    Ok(mismatched)
+}
+
+pub fn find_equilibration(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    window: usize,
+    tolerance: f64,
+) -> Result<Option<usize>, Error> {
+    if window == 0 {
+        return Err(Error::new(InvalidInput, "Window must be positive"));
+    }
+    let temperatures = read_temperatures(file_path, offset, limit, None)?
+        .into_iter()
+        .map(|(_, temperature)| temperature)
+        .collect::<Vec<f64>>();
+    if temperatures.len() < window {
+        return Ok(None);
+    }
+    let mean = temperatures.iter().sum::<f64>() / temperatures.len() as f64;
+    let moving_averages = temperatures
+        .windows(window)
+        .map(|values| values.iter().sum::<f64>() / window as f64);
+    // first window after which every moving average stays within tolerance
+    let mut equilibrated = None;
+    for (frame, average) in moving_averages.enumerate() {
+        if (average - mean).abs() <= tolerance {
+            equilibrated.get_or_insert(frame);
+        } else {
+            equilibrated = None;
+        }
+    }
+    Ok(equilibrated)
+}
+
+pub fn read_out_of_box(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+) -> Result<Vec<(usize, usize)>, Error> {
+    let options = ParseOptions::default();
+    let first_outside = map_frame_lines(file_path, offset, limit, &options, |lines| {
+        let (_, cbox, _) = Configuration::parse_headers(&lines, &options)?;
+        for (index, line) in lines[3..].iter().enumerate() {
+            let nucleotide = Configuration::parse_nucleotide(line)?;
+            if (0..3).any(|axis| !(0.0..cbox[axis]).contains(&nucleotide[axis])) {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    })?;
+    Ok(first_outside
+        .into_iter()
+        .enumerate()
+        .filter_map(|(frame, index)| index.map(|index| (frame, index)))
+        .collect())
+}
+
+pub fn read_nucleotide_count_mode(
+    file_path: &str,
+    offset: u64,
+) -> Result<(usize, Vec<(usize, usize)>), Error> {
+    let mut reader =
+        ConfigReader::new(file_path, offset, SaveLines::None, &ParseOptions::default())?;
+    let mut counts = Vec::new();
+    while let Some(result) = reader.next() {
+        result?;
+        counts.push(reader.line_count.saturating_sub(3));
+    }
+    let mut frequencies = std::collections::HashMap::new();
+    for &count in &counts {
+        *frequencies.entry(count).or_insert(0usize) += 1;
+    }
+    // ties go to the count seen first
+    let mode = counts
+        .iter()
+        .copied()
+        .rev()
+        .max_by_key(|count| frequencies[count])
+        .unwrap_or(0);
+    let outliers = counts
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, count)| count != mode)
+        .collect();
+    Ok((mode, outliers))
+}
+
+#[pyclass]
+pub struct ValidationSummary {
+    #[pyo3(get)]
+    frames: usize,
+    #[pyo3(get)]
+    nucleotide_count: Option<usize>,
+    #[pyo3(get)]
+    first_time: Option<u64>,
+    #[pyo3(get)]
+    last_time: Option<u64>,
+    #[pyo3(get)]
+    problems: Vec<(usize, String)>,
+}
+
+#[pymethods]
+impl ValidationSummary {
+    fn __repr__(&self) -> String {
+        format!(
+            "<ValidationSummary frames={} problems={}>",
+            self.frames,
+            self.problems.len()
+        )
+    }
+}
+
+// headers are parsed, nucleotide lines only counted, and a bad frame is recorded instead of failing the scan,
+// nucleotide counts must match expected_nucleotides if given, e.g. from a topology, or else the first frame
+pub fn validate_frames(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    expected_nucleotides: Option<usize>,
+    options: &ParseOptions,
+) -> Result<ValidationSummary, Error> {
+    let mut reader = ConfigReader::new(file_path, offset, SaveLines::Headers, options)?;
+    let mut summary = ValidationSummary {
+        frames: 0,
+        nucleotide_count: expected_nucleotides,
+        first_time: None,
+        last_time: None,
+        problems: Vec::new(),
+    };
+    while summary.frames < limit {
+        let Some(result) = reader.next() else {
+            break;
+        };
+        let (_, _, lines) = result?;
+        let index = summary.frames;
+        summary.frames += 1;
+        let time = match Configuration::parse_headers(&lines, options) {
+            Ok((time, _, _)) => time,
+            Err(e) => {
+                summary.problems.push((index, e.to_string()));
+                continue;
+            }
+        };
+        summary.first_time.get_or_insert(time);
+        if let Some(last_time) = summary.last_time.filter(|&last_time| time <= last_time) {
+            summary.problems.push((
+                index,
+                format!(
+                    "Configuration at time {} does not come after time {}",
+                    time, last_time
+                ),
+            ));
+        }
+        summary.last_time = Some(time);
+        let count = reader.line_count.saturating_sub(3);
+        let expected = *summary.nucleotide_count.get_or_insert(count);
+        if count != expected {
+            summary.problems.push((
+                index,
+                format!(
+                    "Configuration at time {} has {} nucleotides, expected {}",
+                    time, count, expected
+                ),
+            ));
+        }
+    }
+    Ok(summary)
+}
+
+// start offset of a partial last configuration as drop_partial_frame detects, None if the last one is complete.
+// Only the last configuration is parsed, so it is compared to the one before it by line count
+pub fn find_incomplete_frame(
+    file_path: &str,
+    options: &ParseOptions,
+) -> Result<Option<u64>, Error> {
+    if detect_compression(file_path)?.is_some() {
+        return Err(Error::new(
+            InvalidInput,
+            format!("Cannot repair compressed file {}", file_path),
+        ));
+    }
+    let mut reader = ConfigReader::new(file_path, 0, SaveLines::None, options)?;
+    let mut last = None;
+    let mut previous_line_count = None;
+    while let Some(result) = reader.next() {
+        let (start, _, _) = result?;
+        previous_line_count = last.map(|(_, line_count)| line_count);
+        last = Some((start, reader.line_count));
+    }
+    let Some((start, line_count)) = last else {
+        return Ok(None);
+    };
+    // only the last configuration is read again with its lines, to check for a number cut off in its last line
+    let Some(frame) = ConfigReader::new(file_path, start, SaveLines::All, options)?.next() else {
+        return Ok(None);
+    };
+    let mut frames = vec![parse_frame(frame?, options)];
+    let fewer_lines = previous_line_count.is_some_and(|previous| line_count < previous);
+    Ok((fewer_lines || drop_partial_frame(&mut frames, None, true)).then_some(start))
+}
+
+// cuts a partial last configuration off the file, returns where the file was or would be cut
+pub fn truncate_incomplete_frame(
+    file_path: &str,
+    dry_run: bool,
+    options: &ParseOptions,
+) -> Result<Option<u64>, Error> {
+    let start = find_incomplete_frame(file_path, options)?;
+    if let (Some(start), false) = (start, dry_run) {
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(file_path)?
+            .set_len(start)?;
+    }
+    Ok(start)
+}
+
+pub fn read_orientation_norm_range(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+) -> Result<[f64; 4], Error> {
+    let empty_range = [
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+    ];
+    let merge = |a: [f64; 4], b: [f64; 4]| {
+        [
+            a[0].min(b[0]),
+            a[1].max(b[1]),
+            a[2].min(b[2]),
+            a[3].max(b[3]),
+        ]
+    };
+    let options = ParseOptions::default();
+    let ranges = map_frame_lines(file_path, offset, limit, &options, |lines| {
+        Configuration::parse_headers(&lines, &options)?;
+        lines[3..].iter().try_fold(empty_range, |range, line| {
+            let nucleotide = Configuration::parse_nucleotide(line)?;
+            let a1_norm = nucleotide[3..6].iter().map(|x| x * x).sum::<f64>().sqrt();
+            let a3_norm = nucleotide[6..9].iter().map(|x| x * x).sum::<f64>().sqrt();
+            Ok(merge(range, [a1_norm, a1_norm, a3_norm, a3_norm]))
+        })
+    })?;
+    Ok(ranges.into_iter().fold(empty_range, merge))
+}
+
+pub fn read_offsets(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<u64>, TrajectoryError> {
+    if let Some(entries) = load_frame_index(file_path, options)? {
+        // an offset inside a configuration starts at the next one, as when scanning
+        let first = entries.partition_point(|entry| entry.start < offset);
+        let at_frame_start = entries
+            .get(first)
+            .is_some_and(|entry| entry.start == offset);
+        if at_frame_start || !options.require_frame_start {
+            return Ok(entries[first..]
+                .iter()
+                .take(limit)
+                .map(|entry| entry.end)
+                .collect());
+        }
+    }
+    // the memory-mapped scan covers leading headers read whole, about twice as fast as reading lines
+    if options.header_position == HeaderPosition::Leading
+        && options.end_offset.is_none()
+        && options.stride == 1
+    {
+        return Ok(read_offsets_mmap(file_path, offset, limit, options)?);
+    }
+    let reader = ConfigReader::new(file_path, offset, SaveLines::None, options)?;
+    Ok(reader
+        .take(limit)
+        .map(|result| result.map(|(_, end_offset, _)| end_offset))
+        .collect::<Result<Vec<u64>, Error>>()?)
+}
+
+pub fn read_offsets_with_body(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<(u64, u64)>, Error> {
+    let mut reader = ConfigReader::new(file_path, offset, SaveLines::None, options)?;
+    let mut offsets = Vec::new();
+    while offsets.len() < limit {
+        let Some(result) = reader.next() else {
+            break;
+        };
+        let (_, end_offset, _) = result?;
+        offsets.push((end_offset, reader.body_offset));
+    }
+    Ok(offsets)
+}
+
+pub fn read_lines(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<(u64, Vec<String>)>, Error> {
+    let reader = ConfigReader::new(file_path, offset, SaveLines::All, options)?;
+    reader
+        .take(limit)
+        .map(|result| result.map(|(_, end_offset, lines)| (end_offset, lines)))
+        .collect::<Result<Vec<_>, Error>>()
+}
+
+pub fn read_times(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<u64>, Error> {
+    let reader = ConfigReader::new(file_path, offset, SaveLines::Headers, options)?;
+    reader
+        .take(limit)
+        .map(|result| result.and_then(|(_, _, lines)| Configuration::parse_time(&lines, options)))
+        .collect::<Result<Vec<u64>, Error>>()
+}
+
+// end offset and time of each configuration
+pub fn read_offsets_with_times(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<(u64, u64)>, Error> {
+    let reader = ConfigReader::new(file_path, offset, SaveLines::Headers, options)?;
+    reader
+        .take(limit)
+        .map(|result| {
+            let (_, end_offset, lines) = result?;
+            Ok((end_offset, Configuration::parse_time(&lines, options)?))
+        })
+        .collect()
+}
+
+// nucleotide lines are skipped unparsed, so this costs about as much as reading the times
+pub fn read_box_series(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<(u64, [f64; 3])>, Error> {
+    let reader = ConfigReader::new(file_path, offset, SaveLines::Headers, options)?;
+    reader
+        .take(limit)
+        .map(|result| {
+            let (_, _, lines) = result?;
+            let time = Configuration::parse_time(&lines, options)?;
+            let cbox = Configuration::parse_values(
+                Configuration::get_header(&lines, 1, "b", "box", options)?,
+                3,
+                "box",
+            )
+            .map_err(|e| TrajectoryError::at_frame(e, time))?;
+            Ok((time, [cbox[0], cbox[1], cbox[2]]))
+        })
+        .collect()
+}
+
+// scans only time headers, stopping once past the target while times are increasing
+pub fn find_nearest_time(
+    file_path: &str,
+    target_time: u64,
+    options: &ParseOptions,
+) -> Result<Option<u64>, Error> {
+    let frames: Box<dyn Iterator<Item = Result<(u64, u64), Error>>> =
+        match load_frame_index(file_path, options)? {
+            Some(entries) => Box::new(
+                entries
+                    .into_iter()
+                    .map(|entry| Ok((entry.start, entry.time))),
+            ),
+            None => Box::new(
+                ConfigReader::new(file_path, 0, SaveLines::Headers, options)?.map(|result| {
+                    let (config_start, _, lines) = result?;
+                    Ok((config_start, Configuration::parse_time(&lines, options)?))
+                }),
+            ),
+        };
+    let mut nearest: Option<(u64, u64)> = None;
+    let mut previous_time = None;
+    let mut increasing = true;
+    for result in frames {
+        let (config_start, time) = result?;
+        let distance = time.abs_diff(target_time);
+        if nearest.is_none_or(|(nearest_distance, _)| distance < nearest_distance) {
+            nearest = Some((distance, config_start));
+        }
+        increasing &= previous_time.is_none_or(|previous| previous <= time);
+        previous_time = Some(time);
+        if increasing && time >= target_time {
+            break;
+        }
+    }
+    Ok(nearest.map(|(_, config_start)| config_start))
+}
+
+// energy header values as written in the file, so no float round trip changes their text
+pub fn read_energy_strings(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<String>, Error> {
+    let reader = ConfigReader::new(file_path, offset, SaveLines::Headers, options)?;
+    reader
+        .take(limit)
+        .map(|result| {
+            result.and_then(|(_, _, lines)| {
+                Configuration::get_header(&lines, 2, "E", "energy", options).map(str::to_string)
+            })
+        })
+        .collect::<Result<Vec<String>, Error>>()
+}
+
+// time, box and energy
+pub type Headers = (u64, Vec<f64>, Vec<f64>);
+
+// headers of each configuration, skipping nucleotide lines unparsed
+pub fn read_header_series(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<Headers>, Error> {
+    let reader = ConfigReader::new(file_path, offset, SaveLines::Headers, options)?;
+    reader
+        .take(limit)
+        .map(|result| Configuration::parse_headers(&result?.2, options))
+        .collect()
+}
+
+pub fn read_energy_values(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<Vec<f64>>, Error> {
+    let reader = ConfigReader::new(file_path, offset, SaveLines::Headers, options)?;
+    reader
+        .take(limit)
+        .map(|result| {
+            result.and_then(|(_, _, lines)| {
+                Configuration::parse_tokens(
+                    Configuration::get_header(&lines, 2, "E", "energy", options)?,
+                    "energy",
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()
+}
+
+pub fn downsample(input_path: &str, output_path: &str, stride: usize) -> Result<usize, Error> {
+    if stride == 0 {
+        return Err(Error::new(InvalidInput, "Stride must be positive"));
+    }
+    let reader = ConfigReader::new(input_path, 0, SaveLines::None, &ParseOptions::default())?;
+    let mut input = File::open(input_path)?;
+    let mut output = std::io::BufWriter::new(File::create(output_path)?);
+    let mut buffer = Vec::new();
+    let mut written = 0;
+    for result in reader.step_by(stride) {
+        let (config_start, end_offset, _) = result?;
+        buffer.resize((end_offset - config_start) as usize, 0);
+        input.seek(SeekFrom::Start(config_start))?;
+        input.read_exact(&mut buffer)?;
+        // last configuration of a file without trailing newline
+        if buffer.last() != Some(&b'\n') {
+            buffer.push(b'\n');
+        }
+        output.write_all(&buffer)?;
+        written += 1;
+    }
+    output.flush()?;
+    Ok(written)
+}
+
+// CRC-32 (IEEE 802.3) lookup table, the checksum of zlib and gzip
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+};
+
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        CRC32_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+// checksum of each configuration's bytes from its start to the next one's, without parsing values
+pub fn read_frame_checksums(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<u32>, Error> {
+    let reader = ConfigReader::new(file_path, offset, SaveLines::None, options)?;
+    let mut input = File::open(file_path)?;
+    let mut buffer = Vec::new();
+    reader
+        .take(limit)
+        .map(|result| {
+            let (config_start, end_offset, _) = result?;
+            buffer.resize((end_offset - config_start) as usize, 0);
+            input.seek(SeekFrom::Start(config_start))?;
+            input.read_exact(&mut buffer)?;
+            Ok(crc32(&buffer))
+        })
+        .collect()
+}
+
+pub fn merge_by_time(path_a: &str, path_b: &str, output_path: &str) -> Result<usize, Error> {
+    let options = ParseOptions::default();
+    let mut frames = Vec::new();
+    for (file_index, file_path) in [path_a, path_b].into_iter().enumerate() {
+        let reader = ConfigReader::new(file_path, 0, SaveLines::Headers, &options)?;
+        for result in reader {
+            let (config_start, end_offset, lines) = result?;
+            let time = Configuration::parse_time(&lines, &options)?;
+            frames.push((time, file_index, config_start, end_offset));
+        }
+    }
+    // stable, so equal times keep file order with path_a first, and each file's own order
+    frames.sort_by_key(|&(time, _, _, _)| time);
+    let mut inputs = [File::open(path_a)?, File::open(path_b)?];
+    let mut output = std::io::BufWriter::new(File::create(output_path)?);
+    let mut buffer = Vec::new();
+    for &(_, file_index, config_start, end_offset) in &frames {
+        let input = &mut inputs[file_index];
+        buffer.resize((end_offset - config_start) as usize, 0);
+        input.seek(SeekFrom::Start(config_start))?;
+        input.read_exact(&mut buffer)?;
+        // last configuration of a file without trailing newline
+        if buffer.last() != Some(&b'\n') {
+            buffer.push(b'\n');
+        }
+        output.write_all(&buffer)?;
+    }
+    output.flush()?;
+    Ok(frames.len())
+}
+
+// index of the first newline, testing 8 bytes at a time as memchr does
+fn find_newline(data: &[u8]) -> Option<usize> {
+    const ONES: u64 = u64::from_ne_bytes([0x01; 8]);
+    const HIGHS: u64 = u64::from_ne_bytes([0x80; 8]);
+    const NEWLINES: u64 = u64::from_ne_bytes([b'\n'; 8]);
+    let mut chunks = data.chunks_exact(8);
+    for (index, chunk) in chunks.by_ref().enumerate() {
+        let word = u64::from_ne_bytes(chunk.try_into().expect("chunks are 8 bytes")) ^ NEWLINES;
+        // nonzero only if some byte of word is zero, i.e. a newline in chunk
+        if word.wrapping_sub(ONES) & !word & HIGHS != 0 {
+            return chunk
+                .iter()
+                .position(|&b| b == b'\n')
+                .map(|position| index * 8 + position);
+        }
+    }
+    let remainder = chunks.remainder();
+    remainder
+        .iter()
+        .position(|&b| b == b'\n')
+        .map(|position| data.len() - remainder.len() + position)
+}
+
+pub fn read_offsets_mmap(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    options: &ParseOptions,
+) -> Result<Vec<u64>, Error> {
+    let file = open_uncompressed(file_path)?;
+    let mut end_offsets = Vec::new();
+    if offset >= file.metadata()?.len() {
+        return Ok(end_offsets);
+    }
+    // a file larger than the address space cannot be mapped anyway
+    let offset = usize::try_from(offset).map_err(|e| Error::new(InvalidInput, e))?;
+    // SAFETY: the map is only read for the duration of this call, truncating the file meanwhile is
+    // as undefined here as it is for the line based reader
+    let mmap = unsafe { Mmap::map(&file)? };
+    let data = &mmap[..];
+
+    let is_frame_start = |pos: usize| {
+        let indent = data[pos..]
+            .iter()
+            .take_while(|&&b| b == b' ' || b == b'\t')
+            .count();
+        match data.get(pos + indent) {
+            Some(b't') => true,
+            Some(b'T') => options.case_insensitive_headers,
+            _ => false,
+        }
+    };
+    let next_line_start = |pos: usize| find_newline(&data[pos..]).map(|i| pos + i + 1);
+    let find_frame_start = |mut line_start: Option<usize>| {
+        while let Some(pos) = line_start {
+            if is_frame_start(pos) {
+                break;
+            }
+            if !options.case_insensitive_headers && is_uppercase_time_header(&data[pos..]) {
+                let line_end = next_line_start(pos).unwrap_or(data.len());
+                let line = String::from_utf8_lossy(&data[pos..line_end]);
+                return Err(uppercase_header_error(pos as u64, &line));
+            }
+            line_start = next_line_start(pos);
+        }
+        Ok(line_start)
+    };
+
+    if options.require_frame_start && !is_frame_start(offset) {
+        let line_end = next_line_start(offset).unwrap_or(data.len());
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "Offset {} is not at start of a configuration: {}",
+                offset,
+                String::from_utf8_lossy(&data[offset..line_end]).trim_end()
+            ),
+        ));
+    }
+    let Some(mut config_start) = find_frame_start(Some(offset))? else {
+        return Ok(end_offsets);
+    };
+    while end_offsets.len() < limit {
+        let end_offset = find_frame_start(next_line_start(config_start))?.unwrap_or(data.len());
+        end_offsets.push(end_offset as u64);
+        if end_offset >= data.len() {
+            break;
+        }
+        config_start = end_offset;
+    }
+    Ok(end_offsets)
+}
+
+type PyConfiguration<'py, T = f64> = (
+    u64,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray2<T>>,
+);
+
+fn nucleotides_array<T: NucleotideFloat>(
+    nucleotides: Vec<Vec<f64>>,
+) -> PyResult<numpy::ndarray::Array2<T>> {
+    let shape = (nucleotides.len(), nucleotides.first().map_or(0, Vec::len));
+    let values = nucleotides.into_iter().flatten().map(T::from_f64).collect();
+    numpy::ndarray::Array2::from_shape_vec(shape, values)
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+// nucleotides of each strand in file order, keyed by strand id
+fn group_by_strand(
+    time: u64,
+    nucleotides: Vec<Vec<f64>>,
+    strand_ids: &[usize],
+) -> Result<BTreeMap<usize, Vec<Vec<f64>>>, Error> {
+    if strand_ids.len() != nucleotides.len() {
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "Configuration at time {} has {} nucleotides, got {} strand ids",
+                time,
+                nucleotides.len(),
+                strand_ids.len()
+            ),
+        ));
+    }
+    let mut strands: BTreeMap<usize, Vec<Vec<f64>>> = BTreeMap::new();
+    for (&strand_id, nucleotide) in strand_ids.iter().zip(nucleotides) {
+        strands.entry(strand_id).or_default().push(nucleotide);
+    }
+    Ok(strands)
+}
+
+impl Configuration {
+    fn into_py_configuration<T: NucleotideFloat>(
+        self,
+        py: Python<'_>,
+    ) -> PyResult<PyConfiguration<'_, T>> {
+        let np_box = PyArray1::from_vec(py, self.cbox);
+        let np_energy = PyArray1::from_vec(py, self.cenergy);
+        let np_nucleotides = PyArray2::from_owned_array(py, nucleotides_array(self.nucleotides)?);
+        Ok((self.time, np_box, np_energy, np_nucleotides))
+    }
+}
+
+// nucleotides as a dict of arrays, by strand or by column group
+type PyDictConfiguration<'py> = (
+    u64,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyDict>,
+);
+
+#[derive(IntoPyObject)]
+enum PyFrame<'py, T: NucleotideFloat> {
+    Nucleotides(PyConfiguration<'py, T>),
+    ByStrand(PyDictConfiguration<'py>),
+    ByColumns(PyDictConfiguration<'py>),
+    WithExtraColumns(PyExtraConfiguration<'py, T>),
+}
+
+type PyExtraConfiguration<'py, T> = (
+    u64,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray2<T>>,
+    Bound<'py, PyArray2<T>>,
+);
+
+// names of nucleotide columns in groups of 3, in file order
+const COLUMN_GROUPS: [&str; 5] = ["positions", "a1", "a3", "velocities", "angular_velocities"];
+
+fn check_column_groups(columns: usize) -> PyResult<()> {
+    if !columns.is_multiple_of(3) || columns / 3 > COLUMN_GROUPS.len() {
+        return Err(PyValueError::new_err(format!(
+            "Cannot split {} columns per nucleotide into groups of 3, expected a multiple of 3 up to {}",
+            columns,
+            COLUMN_GROUPS.len() * 3
+        )));
+    }
+    Ok(())
+}
+
+// splits the last axis of nucleotides into named groups of 3 columns
+fn split_column_groups<'py, T: NucleotideFloat, D: numpy::ndarray::RemoveAxis>(
+    py: Python<'py>,
+    nucleotides: numpy::ndarray::Array<T, D>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let axis = numpy::ndarray::Axis(nucleotides.ndim() - 1);
+    let groups = PyDict::new(py);
+    for (index, name) in COLUMN_GROUPS
+        .iter()
+        .enumerate()
+        .take(nucleotides.len_of(axis) / 3)
+    {
+        let group = nucleotides
+            .slice_axis(axis, numpy::ndarray::Slice::from(index * 3..index * 3 + 3))
+            .to_owned();
+        groups.set_item(name, numpy::PyArray::from_owned_array(py, group))?;
+    }
+    Ok(groups)
+}
+
+impl Configuration {
+    fn into_py_strands<'py, T: NucleotideFloat>(
+        self,
+        py: Python<'py>,
+        strand_ids: &[usize],
+    ) -> PyResult<PyDictConfiguration<'py>> {
+        let strands =
+            group_by_strand(self.time, self.nucleotides, strand_ids).map_err(to_py_err)?;
+        let np_strands = PyDict::new(py);
+        for (strand_id, nucleotides) in strands {
+            let np_nucleotides =
+                PyArray2::<T>::from_owned_array(py, nucleotides_array(nucleotides)?);
+            np_strands.set_item(strand_id, np_nucleotides)?;
+        }
+        let np_box = PyArray1::from_vec(py, self.cbox);
+        let np_energy = PyArray1::from_vec(py, self.cenergy);
+        Ok((self.time, np_box, np_energy, np_strands))
+    }
+
+    fn into_py_column_groups<T: NucleotideFloat>(
+        self,
+        py: Python<'_>,
+        extra_columns: bool,
+    ) -> PyResult<PyDictConfiguration<'_>> {
+        let nucleotides = nucleotides_array::<T>(self.nucleotides)?;
+        let groups = split_column_groups(py, nucleotides)?;
+        if extra_columns {
+            let extras = nucleotides_array::<T>(self.extra_columns)?;
+            groups.set_item("extra", PyArray2::from_owned_array(py, extras))?;
+        }
+        let np_box = PyArray1::from_vec(py, self.cbox);
+        let np_energy = PyArray1::from_vec(py, self.cenergy);
+        Ok((self.time, np_box, np_energy, groups))
+    }
+
+    fn into_py_extra_configuration<T: NucleotideFloat>(
+        self,
+        py: Python<'_>,
+    ) -> PyResult<PyExtraConfiguration<'_, T>> {
+        let extras = nucleotides_array::<T>(self.extra_columns)?;
+        let np_nucleotides = PyArray2::from_owned_array(py, nucleotides_array(self.nucleotides)?);
+        let np_box = PyArray1::from_vec(py, self.cbox);
+        let np_energy = PyArray1::from_vec(py, self.cenergy);
+        Ok((
+            self.time,
+            np_box,
+            np_energy,
+            np_nucleotides,
+            PyArray2::from_owned_array(py, extras),
+        ))
+    }
+}
+
+fn into_py_configurations(
+    py: Python<'_>,
+    configs: Vec<(u64, Configuration)>,
+) -> PyResult<(Vec<u64>, Vec<PyConfiguration<'_>>)> {
+    let end_offsets = configs
+        .iter()
+        .map(|(end_offset, _)| end_offset.to_owned())
+        .collect::<Vec<u64>>();
+
+    let confs = configs
+        .into_iter()
+        .map(|(_, conf)| conf.into_py_configuration(py))
+        .collect::<PyResult<Vec<PyConfiguration<'_>>>>()?;
+
+    Ok((end_offsets, confs))
+}
+
+type PyMultiConfigurations<'py> = (Vec<usize>, Vec<u64>, Vec<PyConfiguration<'py>>);
+
+fn into_py_multi_configurations(
+    py: Python<'_>,
+    configs: Vec<(usize, u64, Configuration)>,
+) -> PyResult<PyMultiConfigurations<'_>> {
+    let mut file_indices = Vec::with_capacity(configs.len());
+    let mut end_offsets = Vec::with_capacity(configs.len());
+    let confs = configs
+        .into_iter()
+        .map(|(file_index, end_offset, conf)| {
+            file_indices.push(file_index);
+            end_offsets.push(end_offset);
+            conf.into_py_configuration(py)
+        })
+        .collect::<PyResult<Vec<PyConfiguration<'_>>>>()?;
+    Ok((file_indices, end_offsets, confs))
+}
+
+type TimeSeries<'py, T> = (Bound<'py, PyArray1<u64>>, Bound<'py, T>);
+
+create_exception!(
+    oxdna_trajectory_reader,
+    TrajectoryFormatError,
+    PyValueError,
+    "Malformed trajectory content."
+);
+create_exception!(
+    oxdna_trajectory_reader,
+    MissingHeaderError,
+    TrajectoryFormatError,
+    "A header line is missing or does not have the expected key."
+);
+create_exception!(
+    oxdna_trajectory_reader,
+    BadValueError,
+    TrajectoryFormatError,
+    "A value could not be parsed as a number."
+);
+create_exception!(
+    oxdna_trajectory_reader,
+    CountMismatchError,
+    TrajectoryFormatError,
+    "A line has a different number of values than expected."
+);
+
+fn trajectory_py_err(py: Python<'_>, e: TrajectoryError) -> PyResult<PyErr> {
+    let message = e.to_string();
+    let err = match e {
+        TrajectoryError::Io(e) => return Ok(to_py_err(e)),
+        TrajectoryError::MissingHeader {
+            header,
+            line,
+            location,
+        } => {
+            let err = MissingHeaderError::new_err(message);
+            let value = err.value(py);
+            value.setattr("header", header)?;
+            value.setattr("line", line)?;
+            (err, location)
+        }
+        TrajectoryError::BadValue {
+            frame,
+            line,
+            value,
+            location,
+        } => {
+            let err = BadValueError::new_err(message);
+            let exc = err.value(py);
+            exc.setattr("frame", frame)?;
+            exc.setattr("line", line)?;
+            exc.setattr("value", value)?;
+            (err, location)
+        }
+        TrajectoryError::CountMismatch {
+            frame,
+            name,
+            line,
+            expected,
+            found,
+            location,
+        } => {
+            let err = CountMismatchError::new_err(message);
+            let value = err.value(py);
+            value.setattr("frame", frame)?;
+            value.setattr("name", name)?;
+            value.setattr("line", line)?;
+            value.setattr("expected", expected)?;
+            value.setattr("found", found)?;
+            (err, location)
+        }
+    };
+    let (err, location) = err;
+    let value = err.value(py);
+    value.setattr("index", location.index)?;
+    value.setattr("line_number", location.line_number)?;
+    value.setattr("offset", location.offset)?;
+    Ok(err)
+}
+
+fn to_py_err(e: impl Into<Error>) -> PyErr {
+    let e = e.into();
+    if e.get_ref()
+        .is_some_and(|inner| inner.is::<TrajectoryError>())
+    {
+        return Python::attach(|py| {
+            trajectory_py_err(py, TrajectoryError::from(e)).unwrap_or_else(|err| err)
+        });
+    }
+    if e.kind() == InvalidInput {
+        return PyValueError::new_err(e.to_string());
+    }
+    // exceptions raised by a Python file-like object keep their type
+    if e.get_ref().is_some_and(|inner| inner.is::<PyErr>()) {
+        let inner = e.into_inner().expect("checked to have an inner error");
+        return *inner.downcast::<PyErr>().expect("checked to be a PyErr");
+    }
+    PyIOError::new_err(e.to_string())
+}
+
+#[pyclass]
+struct ReadResult {
+    #[pyo3(get)]
+    end_offsets: Vec<u64>,
+    // a list of configurations, or a tuple of arrays when stacked
+    #[pyo3(get)]
+    configurations: Py<PyAny>,
+    #[pyo3(get)]
+    bytes_read: u64,
+    #[pyo3(get)]
+    discarded_partial: bool,
+    #[pyo3(get)]
+    energy_count: Option<usize>,
+    #[pyo3(get)]
+    skipped: Vec<(usize, String)>,
+}
+
+#[pymethods]
+impl ReadResult {
+    // unpacks as (end_offsets, configurations) like the tuple returned before
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        self.as_tuple(py)?.try_iter()
+    }
+
+    fn __len__(&self) -> usize {
+        2
+    }
+
+    fn __getitem__<'py>(&self, py: Python<'py>, index: isize) -> PyResult<Bound<'py, PyAny>> {
+        self.as_tuple(py)?.as_any().get_item(index)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<ReadResult configurations={} bytes_read={}>",
+            self.end_offsets.len(),
+            self.bytes_read
+        )
+    }
+}
+
+impl ReadResult {
+    fn as_tuple<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        (self.end_offsets.clone(), self.configurations.bind(py)).into_pyobject(py)
+    }
+}
+
+// configurations, whether a cut off last configuration was dropped,
+// and index from offset and reason of each skipped configuration
+type PyReadConfs = (Vec<(u64, Configuration)>, bool, Vec<(usize, String)>);
+
+// reads frames and parses each chunk in parallel without the GIL, so the callback runs between chunks
+// and the reader is never used from two threads at once
+fn read_py_confs_chunked<R: BufRead + Send>(
+    py: Python<'_>,
+    reader: &mut ConfigReader<R>,
+    limit: usize,
+    options: &ParseOptions,
+    strict: bool,
+    skip_errors: bool,
+    progress: Option<&Bound<'_, PyAny>>,
+) -> PyResult<PyReadConfs> {
+    let mut configs = Vec::new();
+    let mut skipped = Vec::new();
+    let mut frames_read = 0;
+    let mut discarded_partial = false;
+    while frames_read < limit {
+        let chunk_size = FRAME_CHUNK.min(limit - frames_read);
+        let mut frames = py
+            .detach(|| -> Result<Vec<_>, Error> {
+                let chunk = reader
+                    .by_ref()
+                    .take(chunk_size)
+                    .collect::<Result<Vec<_>, Error>>()?;
+                Ok(in_thread_pool(|| {
+                    chunk
+                        .into_par_iter()
+                        .map(|frame| parse_frame(frame, options))
+                        .collect()
+                }))
+            })
+            .map_err(to_py_err)?;
+        if frames.is_empty() {
+            break;
+        }
+        let first_index = frames_read;
+        frames_read += frames.len();
+        if !strict {
+            let previous = configs
+                .last()
+                .map(|(_, conf): &(u64, Configuration)| conf.nucleotides.len());
+            discarded_partial = drop_partial_frame(&mut frames, previous, reader.at_end_of_input());
+        }
+        for (index, result) in frames.into_iter().enumerate() {
+            match result {
+                Ok(config) => configs.push(config),
+                // a skipped configuration ends at the next configuration start, where reading continues,
+                // its line number is left out as counting lines for each would read the file again
+                Err(e) if skip_errors => skipped.push((
+                    first_index + index,
+                    TrajectoryError::at_index(e, first_index + index).to_string(),
+                )),
+                Err(e) => {
+                    let e = TrajectoryError::at_index(e, first_index + index);
+                    return Err(to_py_err(reader.with_line_number(e)));
+                }
+            }
+        }
+        if let (Some(progress), Some(&(end_offset, _))) = (progress, configs.last()) {
+            progress.call1((end_offset, configs.len()))?;
+        }
+    }
+    Ok((configs, discarded_partial, skipped))
+}
+
+fn read_py_confs<R: BufRead + Send>(
+    py: Python<'_>,
+    reader: &mut ConfigReader<R>,
+    limit: usize,
+    options: &ParseOptions,
+    strict: bool,
+    skip_errors: bool,
+    progress: Option<&Bound<'_, PyAny>>,
+) -> PyResult<PyReadConfs> {
+    match progress {
+        None if skip_errors => {
+            read_py_confs_chunked(py, reader, limit, options, strict, true, None)
+        }
+        None if strict => Ok((
+            py.detach(|| read_confs_from(reader, limit, options))
+                .map_err(to_py_err)?,
+            false,
+            Vec::new(),
+        )),
+        None => {
+            let (configs, discarded_partial) = py
+                .detach(|| read_confs_dropping_partial(reader, limit, options))
+                .map_err(to_py_err)?;
+            Ok((configs, discarded_partial, Vec::new()))
+        }
+        Some(_) => read_py_confs_chunked(py, reader, limit, options, strict, skip_errors, progress),
+    }
+}
+
+#[derive(FromPyObject)]
+enum TrajectorySource {
+    Path(String),
+    FileLike(Py<PyAny>),
+}
+
+// magic bytes of compressed files, read through the first of their Python modules that imports
+const COMPRESSIONS: [(&[u8], &[&str]); 4] = [
+    (&[0x1f, 0x8b], &["gzip"]),
+    (b"BZh", &["bz2"]),
+    (&[0xfd, b'7', b'z', b'X', b'Z', 0x00], &["lzma"]),
+    (&[0x28, 0xb5, 0x2f, 0xfd], ZSTD_MODULES),
+];
+
+// compression.zstd is in the standard library from Python 3.14
+const ZSTD_MODULES: &[&str] = &["compression.zstd", "zstandard"];
+
+fn detect_compression(file_path: &str) -> Result<Option<&'static [&'static str]>, Error> {
+    compression_of(&mut File::open(file_path)?)
+}
+
+fn compression_of(file: &mut File) -> Result<Option<&'static [&'static str]>, Error> {
+    let mut magic = Vec::new();
+    file.take(6).read_to_end(&mut magic)?;
+    Ok(COMPRESSIONS
+        .iter()
+        .find(|(compression_magic, _)| magic.starts_with(compression_magic))
+        .map(|(_, modules)| *modules))
+}
+
+// offsets into a compressed file would count compressed bytes and not match those of read_configurations,
+// so every reader other than it rejects compressed trajectories instead of parsing their bytes
+fn open_uncompressed(file_path: &str) -> Result<File, Error> {
+    let mut file = File::open(file_path)?;
+    if let Some(modules) = compression_of(&mut file)? {
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "{} is compressed ({}), only read_configurations reads compressed trajectories",
+                file_path, modules[0]
+            ),
+        ));
+    }
+    file.seek(SeekFrom::Start(0))?;
+    Ok(file)
+}
+
+fn open_compressed<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    modules: &[&str],
+) -> PyResult<Bound<'py, PyAny>> {
+    let mut import_error = None;
+    for name in modules {
+        match py.import(*name) {
+            Ok(module) => return module.call_method1("open", (file_path, "rb")),
+            Err(e) => import_error = Some(e),
+        }
+    }
+    Err(import_error.expect("every compression has a module"))
+}
+
+// adapts a Python file-like object to Read by calling its read method,
+// text mode objects give their text encoded as UTF-8
+struct PyFileReader {
+    file: Py<PyAny>,
+    pending: Vec<u8>,
+    position: usize,
+}
+
+impl PyFileReader {
+    // seeks to offset if the object supports it, otherwise skips offset bytes from where it is
+    fn open(file: Bound<'_, PyAny>, offset: u64) -> PyResult<LineReader<BufReader<Self>>> {
+        if !file.hasattr("read")? {
+            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
+                "Expected a path or a file-like object with a read method, got {}",
+                file.get_type().name()?
+            )));
+        }
+        let seekable = match file.getattr("seekable") {
+            Ok(seekable) => seekable.call0()?.is_truthy()?,
+            Err(_) => file.hasattr("seek")?,
+        };
+        if seekable {
+            file.call_method1("seek", (offset,))?;
+        }
+        let mut reader = BufReader::new(Self {
+            file: file.unbind(),
+            pending: Vec::new(),
+            position: 0,
+        });
+        if !seekable {
+            std::io::copy(&mut reader.by_ref().take(offset), &mut std::io::sink())?;
+        }
+        Ok(LineReader::from_reader(reader, offset))
+    }
+}
+
+impl Read for PyFileReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if self.position == self.pending.len() {
+            self.pending = Python::attach(|py| -> PyResult<Vec<u8>> {
+                let data = self.file.bind(py).call_method1("read", (buf.len(),))?;
+                match data.cast::<pyo3::types::PyString>() {
+                    Ok(text) => Ok(text.to_cow()?.as_bytes().to_vec()),
+                    Err(_) => Ok(data.cast::<pyo3::types::PyBytes>()?.as_bytes().to_vec()),
+                }
+            })
+            .map_err(Error::other)?;
+            self.position = 0;
+        }
+        let count = buf.len().min(self.pending.len() - self.position);
+        buf[..count].copy_from_slice(&self.pending[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+const ZSTD_SKIPPABLE_SEEK_TABLE_MAGIC: u32 = 0x184D_2A5E;
+const ZSTD_SEEKABLE_MAGIC: u32 = 0x8F92_EAB1;
+// skippable frame header of magic and frame size, and footer of frame count, descriptor and magic
+const ZSTD_SEEK_TABLE_HEADER: u64 = 8;
+const ZSTD_SEEK_TABLE_FOOTER: u64 = 9;
+
+// frame table of a file in the zstd seekable format, a skippable frame at its end listing the compressed and
+// decompressed size of each frame, as (compressed start, decompressed start) of each frame followed by the
+// ends of both, None if the file has no seek table
+fn read_zstd_seek_table(file: &mut File) -> Result<Option<Vec<(u64, u64)>>, Error> {
+    let file_length = file.metadata()?.len();
+    if file_length < ZSTD_SEEK_TABLE_HEADER + ZSTD_SEEK_TABLE_FOOTER {
+        return Ok(None);
+    }
+    let mut footer = [0u8; ZSTD_SEEK_TABLE_FOOTER as usize];
+    file.seek(SeekFrom::End(-(ZSTD_SEEK_TABLE_FOOTER as i64)))?;
+    file.read_exact(&mut footer)?;
+    let u32_at = |bytes: &[u8], at: usize| {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().expect("slice is 4 bytes"))
+    };
+    if u32_at(&footer, 5) != ZSTD_SEEKABLE_MAGIC {
+        return Ok(None);
+    }
+    let invalid = |message: &str| {
+        Error::new(
+            InvalidInput,
+            format!("Invalid zstd seek table: {}", message),
+        )
+    };
+    let descriptor = footer[4];
+    if descriptor & 0b0111_1100 != 0 {
+        return Err(invalid("reserved descriptor bits are set"));
+    }
+    // each entry has compressed and decompressed size, and a checksum if the descriptor's top bit is set
+    let entry_size = if descriptor & 0x80 != 0 { 12 } else { 8 };
+    let frame_count = u32_at(&footer, 0) as u64;
+    let table_size = ZSTD_SEEK_TABLE_HEADER + frame_count * entry_size + ZSTD_SEEK_TABLE_FOOTER;
+    if table_size > file_length {
+        return Err(invalid("longer than the file"));
+    }
+    let mut table = vec![0u8; (table_size - ZSTD_SEEK_TABLE_FOOTER) as usize];
+    file.seek(SeekFrom::Start(file_length - table_size))?;
+    file.read_exact(&mut table)?;
+    if u32_at(&table, 0) != ZSTD_SKIPPABLE_SEEK_TABLE_MAGIC
+        || u32_at(&table, 4) as u64 != table_size - ZSTD_SEEK_TABLE_HEADER
+    {
+        return Err(invalid("not in a skippable frame"));
+    }
+    let mut frames = Vec::with_capacity(frame_count as usize + 1);
+    let (mut compressed_start, mut decompressed_start) = (0u64, 0u64);
+    for entry in table[ZSTD_SEEK_TABLE_HEADER as usize..].chunks_exact(entry_size as usize) {
+        frames.push((compressed_start, decompressed_start));
+        compressed_start += u32_at(entry, 0) as u64;
+        decompressed_start += u32_at(entry, 4) as u64;
+    }
+    if compressed_start != file_length - table_size {
+        return Err(invalid("frame sizes do not add up to the file size"));
+    }
+    frames.push((compressed_start, decompressed_start));
+    Ok(Some(frames))
+}
+
+// decompresses one zstd frame given its decompressed size from the seek table
+type DecompressFrame = Box<dyn FnMut(&[u8], u64) -> Result<Vec<u8>, Error> + Send>;
+
+// decompresses through `compression.zstd` or `zstandard`, taking the GIL for each frame
+fn python_zstd_decompress(py: Python<'_>, modules: &[&str]) -> PyResult<DecompressFrame> {
+    let mut import_error = None;
+    for name in modules {
+        let module = match py.import(*name) {
+            Ok(module) => module,
+            Err(e) => {
+                import_error = Some(e);
+                continue;
+            }
+        };
+        // zstandard needs the decompressed size of frames that do not store it
+        let sized = *name == "zstandard";
+        let decompress = if sized {
+            module
+                .call_method0("ZstdDecompressor")?
+                .getattr("decompress")?
+        } else {
+            module.getattr("decompress")?
+        }
+        .unbind();
+        return Ok(Box::new(move |compressed: &[u8], size: u64| {
+            Python::attach(|py| -> PyResult<Vec<u8>> {
+                let decompress = decompress.bind(py);
+                let data = pyo3::types::PyBytes::new(py, compressed);
+                let frame = if sized {
+                    let kwargs = PyDict::new(py);
+                    kwargs.set_item("max_output_size", size)?;
+                    decompress.call((data,), Some(&kwargs))?
+                } else {
+                    decompress.call1((data,))?
+                };
+                Ok(frame.cast::<pyo3::types::PyBytes>()?.as_bytes().to_vec())
+            })
+            .map_err(Error::other)
+        }));
+    }
+    Err(import_error.expect("zstd has modules"))
+}
+
+// reads a seekable zstd file from a decompressed offset, decompressing only the frames from the one containing it
+struct SeekableZstdReader<F: Read + Seek = File> {
+    file: F,
+    frames: Vec<(u64, u64)>,
+    next_frame: usize,
+    decompress: DecompressFrame,
+    pending: Vec<u8>,
+    position: usize,
+}
+
+impl<F: Read + Seek> SeekableZstdReader<F> {
+    fn new(
+        mut file: F,
+        frames: Vec<(u64, u64)>,
+        decompress: DecompressFrame,
+        offset: u64,
+    ) -> Result<Self, Error> {
+        // frame containing offset, or the end past the last frame
+        let frame = frames
+            .partition_point(|&(_, decompressed_start)| decompressed_start <= offset)
+            .saturating_sub(1);
+        file.seek(SeekFrom::Start(frames[frame].0))?;
+        let mut reader = Self {
+            file,
+            frames,
+            next_frame: frame,
+            decompress,
+            pending: Vec::new(),
+            position: 0,
+        };
+        reader.read_frame()?;
+        reader.position =
+            (offset - reader.frames[frame].1).min(reader.pending.len() as u64) as usize;
+        Ok(reader)
+    }
+
+    fn read_frame(&mut self) -> Result<(), Error> {
+        self.pending.clear();
+        self.position = 0;
+        let Some(&[(compressed_start, decompressed_start), (compressed_end, decompressed_end)]) =
+            self.frames.get(self.next_frame..self.next_frame + 2)
+        else {
+            return Ok(());
+        };
+        self.next_frame += 1;
+        let mut compressed = vec![0u8; (compressed_end - compressed_start) as usize];
+        self.file.read_exact(&mut compressed)?;
+        let size = decompressed_end - decompressed_start;
+        self.pending = (self.decompress)(&compressed, size)?;
+        if self.pending.len() as u64 != size {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "zstd frame {} decompressed to {} bytes, its seek table entry has {}",
+                    self.next_frame - 1,
+                    self.pending.len(),
+                    size
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<F: Read + Seek> Read for SeekableZstdReader<F> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        // frames may decompress to nothing, so keep going until data or the end
+        while self.position == self.pending.len() && self.next_frame + 1 < self.frames.len() {
+            self.read_frame()?;
+        }
+        let count = buf.len().min(self.pending.len() - self.position);
+        buf[..count].copy_from_slice(&self.pending[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+// options of read_configurations, which also takes each as a keyword argument overriding these
+#[pyclass(get_all, set_all)]
+#[derive(Clone)]
+struct ReadOptions {
+    case_insensitive_headers: bool,
+    nucleotide_range: Option<(usize, usize)>,
+    require_frame_start: bool,
+    reject_nonfinite: bool,
+    columns_per_nucleotide: Option<usize>,
+    end_offset: Option<u64>,
+    strand_ids: Option<Vec<usize>>,
+    header_position: String,
+    strict: bool,
+    strict_energy_count: Option<usize>,
+    check_energy_count: bool,
+    validate_box: bool,
+    stride: usize,
+    stacked: bool,
+    split_columns: bool,
+    dtype: String,
+    extra_columns: bool,
+    errors: String,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            case_insensitive_headers: false,
+            nucleotide_range: None,
+            require_frame_start: false,
+            reject_nonfinite: false,
+            columns_per_nucleotide: None,
+            end_offset: None,
+            strand_ids: None,
+            header_position: "leading".to_string(),
+            strict: false,
+            strict_energy_count: None,
+            check_energy_count: true,
+            validate_box: false,
+            stride: 1,
+            stacked: false,
+            split_columns: false,
+            dtype: "float64".to_string(),
+            extra_columns: false,
+            errors: "raise".to_string(),
+        }
+    }
+}
+
+impl ReadOptions {
+    // sets each keyword argument as the option of the same name, converted like an attribute assignment
+    fn with_kwargs(self, py: Python<'_>, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let Some(kwargs) = kwargs else {
+            return Ok(self);
+        };
+        let options = Bound::new(py, self)?;
+        for (key, value) in kwargs {
+            let key = key.extract::<String>()?;
+            if !options.hasattr(key.as_str())? {
+                return Err(pyo3::exceptions::PyTypeError::new_err(format!(
+                    "Unexpected keyword argument \"{}\"",
+                    key
+                )));
+            }
+            options.setattr(key.as_str(), value)?;
+        }
+        let options = options.borrow().clone();
+        Ok(options)
+    }
+}
+
+#[pymethods]
+impl ReadOptions {
+    #[new]
+    #[pyo3(signature = (**kwargs))]
+    fn new(py: Python<'_>, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        Self::default().with_kwargs(py, kwargs)
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, limit, options=None, progress=None, **kwargs))]
+fn read_configurations<'py>(
+    py: Python<'py>,
+    file_path: TrajectorySource,
+    offset: u64,
+    limit: usize,
+    options: Option<PyRef<'py, ReadOptions>>,
+    progress: Option<Bound<'py, PyAny>>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<ReadResult> {
+    let ReadOptions {
+        case_insensitive_headers,
+        nucleotide_range,
+        require_frame_start,
+        reject_nonfinite,
+        columns_per_nucleotide,
+        end_offset,
+        strand_ids,
+        header_position,
+        strict,
+        strict_energy_count,
+        check_energy_count,
+        validate_box,
+        stride,
+        stacked,
+        split_columns,
+        dtype,
+        extra_columns,
+        errors,
+    } = options
+        .map_or_else(ReadOptions::default, |options| options.clone())
+        .with_kwargs(py, kwargs)?;
+    if stride == 0 {
+        return Err(PyValueError::new_err("Stride must be positive"));
+    }
+    if (stacked || split_columns || extra_columns) && strand_ids.is_some() {
+        return Err(PyValueError::new_err(
+            "Configurations grouped by strand cannot be stacked, split by columns or have extra columns",
+        ));
+    }
+    if let (true, Some(columns)) = (split_columns, columns_per_nucleotide) {
+        check_column_groups(columns)?;
+    }
+    let float32 = match dtype.as_str() {
+        "float64" => false,
+        "float32" => true,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "Invalid dtype \"{}\", expected \"float64\" or \"float32\"",
+                dtype
+            )))
+        }
+    };
+    let skip_errors = match errors.as_str() {
+        "raise" => false,
+        "skip" => true,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "Invalid errors \"{}\", expected \"raise\" or \"skip\"",
+                errors
+            )))
+        }
+    };
+    let header_position = match header_position.as_str() {
+        "leading" => HeaderPosition::Leading,
+        "trailing" => HeaderPosition::Trailing,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "Invalid header position \"{}\", expected \"leading\" or \"trailing\"",
+                header_position
+            )))
+        }
+    };
+    let options = ParseOptions {
+        case_insensitive_headers,
+        require_frame_start,
+        nucleotide_range,
+        reject_nonfinite,
+        columns_per_nucleotide,
+        extra_columns,
+        end_offset,
+        header_position,
+        validate_box,
+        stride,
+    };
+    let read_file_like = |file: Bound<'py, PyAny>| -> PyResult<_> {
+        let reader = PyFileReader::open(file, offset)?;
+        let mut reader = ConfigReader::from_reader(reader, SaveLines::All, &options);
+        let (configs, discarded_partial, skipped) = read_py_confs_chunked(
+            py,
+            &mut reader,
+            limit,
+            &options,
+            strict,
+            skip_errors,
+            progress.as_ref(),
+        )?;
+        Ok((
+            configs,
+            discarded_partial,
+            skipped,
+            reader.reader.cursor_offset - offset,
+        ))
+    };
+    let compression = match &file_path {
+        TrajectorySource::Path(file_path) => detect_compression(file_path).map_err(to_py_err)?,
+        TrajectorySource::FileLike(_) => None,
+    };
+    let (configs, discarded_partial, skipped, bytes_read) = match (file_path, compression) {
+        // offsets count decompressed bytes, as the decompressing files seek in the decompressed stream
+        (TrajectorySource::Path(file_path), Some(modules)) if modules == ZSTD_MODULES => {
+            let mut file = File::open(&file_path).map_err(to_py_err)?;
+            match read_zstd_seek_table(&mut file).map_err(to_py_err)? {
+                // seekable zstd only decompresses from the frame containing offset
+                Some(frames) => {
+                    let decompress = python_zstd_decompress(py, modules)?;
+                    let reader = SeekableZstdReader::new(file, frames, decompress, offset)
+                        .map_err(to_py_err)?;
+                    let reader = LineReader::from_reader(BufReader::new(reader), offset);
+                    let mut reader = ConfigReader::from_reader(reader, SaveLines::All, &options);
+                    let (configs, discarded_partial, skipped) = read_py_confs_chunked(
+                        py,
+                        &mut reader,
+                        limit,
+                        &options,
+                        strict,
+                        skip_errors,
+                        progress.as_ref(),
+                    )?;
+                    (
+                        configs,
+                        discarded_partial,
+                        skipped,
+                        reader.reader.cursor_offset - offset,
+                    )
+                }
+                None => {
+                    let file = open_compressed(py, &file_path, modules)?;
+                    let result = read_file_like(file.clone());
+                    file.call_method0("close")?;
+                    result?
+                }
+            }
+        }
+        (TrajectorySource::Path(file_path), Some(modules)) => {
+            let file = open_compressed(py, &file_path, modules)?;
+            let result = read_file_like(file.clone());
+            file.call_method0("close")?;
+            result?
+        }
+        (TrajectorySource::Path(file_path), None) => {
+            let mut reader = py
+                .detach(|| ConfigReader::new(&file_path, offset, SaveLines::All, &options))
+                .map_err(to_py_err)?;
+            let (configs, discarded_partial, skipped) = read_py_confs(
+                py,
+                &mut reader,
+                limit,
+                &options,
+                strict,
+                skip_errors,
+                progress.as_ref(),
+            )?;
+            (
+                configs,
+                discarded_partial,
+                skipped,
+                reader.reader.cursor_offset - offset,
+            )
+        }
+        (TrajectorySource::FileLike(file), _) => read_file_like(file.into_bound(py))?,
+    };
+    // frames must have as many energy values as the first one, unless turned off for files mixing counts
+    let energy_count = if check_energy_count || strict_energy_count.is_some() {
+        check_energy_counts(&configs, strict_energy_count).map_err(to_py_err)?
+    } else {
+        uniform_energy_count(&configs)
+    };
+    let (end_offsets, confs): (Vec<u64>, Vec<Configuration>) = configs.into_iter().unzip();
+    let strand_ids = strand_ids.as_deref();
+    let columns = columns_per_nucleotide.unwrap_or(15);
+    let configurations = match (stacked, float32) {
+        (true, false) => {
+            into_py_stacked::<f64>(py, confs, columns, split_columns, extra_columns)?.into_any()
+        }
+        (true, true) => {
+            into_py_stacked::<f32>(py, confs, columns, split_columns, extra_columns)?.into_any()
+        }
+        (false, false) => {
+            into_py_frames::<f64>(py, confs, strand_ids, split_columns, extra_columns)?.into_any()
+        }
+        (false, true) => {
+            into_py_frames::<f32>(py, confs, strand_ids, split_columns, extra_columns)?.into_any()
+        }
+    };
+    Ok(ReadResult {
+        end_offsets,
+        configurations: configurations.unbind(),
+        bytes_read,
+        discarded_partial,
+        energy_count,
+        skipped,
+    })
+}
+
+fn into_py_frames<'py, T: NucleotideFloat + IntoPyObject<'py>>(
+    py: Python<'py>,
+    confs: Vec<Configuration>,
+    strand_ids: Option<&[usize]>,
+    split_columns: bool,
+    extra_columns: bool,
+) -> PyResult<Bound<'py, PyList>> {
+    let frames = confs
+        .into_iter()
+        .map(|conf| {
+            Ok(match strand_ids {
+                None if split_columns => {
+                    PyFrame::ByColumns(conf.into_py_column_groups::<T>(py, extra_columns)?)
+                }
+                None if extra_columns => {
+                    PyFrame::WithExtraColumns(conf.into_py_extra_configuration::<T>(py)?)
+                }
+                None => PyFrame::Nucleotides(conf.into_py_configuration::<T>(py)?),
+                Some(strand_ids) => PyFrame::ByStrand(conf.into_py_strands::<T>(py, strand_ids)?),
+            })
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    PyList::new(py, frames)
+}
+
+fn into_py_stacked<'py, T: NucleotideFloat>(
+    py: Python<'py>,
+    confs: Vec<Configuration>,
+    columns: usize,
+    split_columns: bool,
+    extra_columns: bool,
+) -> PyResult<Bound<'py, PyTuple>> {
+    let (times, boxes, energies, nucleotides, extras) = py
+        .detach(|| stack_confs::<T>(confs, columns))
+        .map_err(to_py_err)?;
+    let np_extras = PyArray3::from_owned_array(py, extras);
+    let np_nucleotides = match split_columns {
+        true => {
+            let groups = split_column_groups(py, nucleotides)?;
+            if extra_columns {
+                groups.set_item("extra", np_extras.clone())?;
+            }
+            groups.into_any()
+        }
+        false => PyArray3::from_owned_array(py, nucleotides).into_any(),
+    };
+    let mut arrays = vec![
+        PyArray1::from_vec(py, times).into_any(),
+        PyArray2::from_owned_array(py, boxes).into_any(),
+        PyArray2::from_owned_array(py, energies).into_any(),
+        np_nucleotides,
+    ];
+    if extra_columns && !split_columns {
+        arrays.push(np_extras.into_any());
+    }
+    PyTuple::new(py, arrays)
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offsets, start_index, count, case_insensitive_headers=false))]
+fn read_configurations_by_index<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offsets: Vec<u64>,
+    start_index: usize,
+    count: usize,
+    case_insensitive_headers: bool,
+) -> PyResult<(Vec<u64>, Vec<PyConfiguration<'py>>)> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        require_frame_start: true,
+        ..Default::default()
+    };
+    let configs = py
+        .detach(|| read_confs_by_index(file_path, &offsets, start_index, count, &options))
+        .map_err(to_py_err)?;
+    into_py_configurations(py, configs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, target_time, case_insensitive_headers=false))]
+fn find_configuration_by_time<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    target_time: u64,
+    case_insensitive_headers: bool,
+) -> PyResult<(u64, PyConfiguration<'py>)> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        require_frame_start: true,
+        ..Default::default()
+    };
+    let offset = py
+        .detach(|| find_nearest_time(file_path, target_time, &options))
+        .map_err(to_py_err)?
+        .ok_or_else(|| PyValueError::new_err(format!("No configuration in \"{}\"", file_path)))?;
+    let configs = py
+        .detach(|| read_confs(file_path, offset, 1, &options))
+        .map_err(to_py_err)?;
+    let (_, mut confs) = into_py_configurations(py, configs)?;
+    let conf = confs
+        .pop()
+        .ok_or_else(|| PyValueError::new_err(format!("No configuration at offset {}", offset)))?;
+    Ok((offset, conf))
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offsets, limit, case_insensitive_headers=false))]
+fn read_configurations_parallel<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offsets: Vec<u64>,
+    limit: usize,
+    case_insensitive_headers: bool,
+) -> PyResult<(Vec<u64>, Vec<PyConfiguration<'py>>)> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    let configs = py
+        .detach(|| read_confs_parallel(file_path, &offsets, limit, &options))
+        .map_err(to_py_err)?;
+    into_py_configurations(py, configs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, indices, case_insensitive_headers=false))]
+fn read_frames<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    indices: Vec<usize>,
+    case_insensitive_headers: bool,
+) -> PyResult<(Vec<u64>, Vec<PyConfiguration<'py>>)> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    let configs = py
+        .detach(|| read_confs_at_indices(file_path, &indices, &options))
+        .map_err(to_py_err)?;
+    into_py_configurations(py, configs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, n, case_insensitive_headers=false))]
+fn read_last<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    n: usize,
+    case_insensitive_headers: bool,
+) -> PyResult<(Vec<u64>, Vec<PyConfiguration<'py>>)> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    let configs = py
+        .detach(|| read_last_confs(file_path, n, &options))
+        .map_err(to_py_err)?;
+    into_py_configurations(py, configs)
+}
+
+#[pyfunction]
+fn read_lammps_configurations<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+) -> PyResult<(Vec<u64>, Vec<PyConfiguration<'py>>)> {
+    let configs = py
+        .detach(|| read_lammps_dump(file_path, offset, limit))
+        .map_err(to_py_err)?;
+    into_py_configurations(py, configs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (limit, case_insensitive_headers=false))]
+fn read_configurations_from_stdin<'py>(
+    py: Python<'py>,
+    limit: usize,
+    case_insensitive_headers: bool,
+) -> PyResult<(Vec<u64>, Vec<PyConfiguration<'py>>)> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    let configs = py
+        .detach(|| read_confs_stdin(limit, &options))
+        .map_err(to_py_err)?;
+    into_py_configurations(py, configs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_paths, limit, case_insensitive_headers=false))]
+fn read_configurations_multi<'py>(
+    py: Python<'py>,
+    file_paths: Vec<String>,
+    limit: usize,
+    case_insensitive_headers: bool,
+) -> PyResult<PyMultiConfigurations<'py>> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    let configs = py
+        .detach(|| read_confs_multi(&file_paths, limit, &options))
+        .map_err(to_py_err)?;
+    into_py_multi_configurations(py, configs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (specs, case_insensitive_headers=false))]
+fn read_configurations_multi_parallel<'py>(
+    py: Python<'py>,
+    specs: Vec<(String, u64, usize)>,
+    case_insensitive_headers: bool,
+) -> PyResult<PyMultiConfigurations<'py>> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    let configs = py
+        .detach(|| read_confs_multi_parallel(&specs, &options))
+        .map_err(to_py_err)?;
+    into_py_multi_configurations(py, configs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, limit, case_insensitive_headers=false))]
+fn read_raw_lines(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    case_insensitive_headers: bool,
+) -> PyResult<(Vec<u64>, Vec<Vec<String>>)> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    Ok(read_lines(file_path, offset, limit, &options)
+        .map_err(to_py_err)?
+        .into_iter()
+        .unzip())
+}
+
+#[derive(IntoPyObject)]
+enum PyOffsets {
+    EndOffsets(Vec<u64>),
+    WithBodyOffsets(Vec<u64>, Vec<u64>),
+    WithTimes(Vec<u64>, Vec<u64>),
+}
+
+#[pyfunction]
+#[pyo3(signature = (
+    file_path,
+    offset,
+    limit,
+    case_insensitive_headers=false,
+    require_frame_start=false,
+    return_body_offsets=false,
+    return_times=false,
+))]
+fn read_indicies(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    case_insensitive_headers: bool,
+    require_frame_start: bool,
+    return_body_offsets: bool,
+    return_times: bool,
+) -> PyResult<PyOffsets> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        require_frame_start,
+        ..Default::default()
+    };
+    match (return_body_offsets, return_times) {
+        (false, false) => Ok(PyOffsets::EndOffsets(
+            read_offsets(file_path, offset, limit, &options).map_err(to_py_err)?,
+        )),
+        (true, false) => {
+            let (end_offsets, body_offsets) =
+                read_offsets_with_body(file_path, offset, limit, &options)
+                    .map_err(to_py_err)?
+                    .into_iter()
+                    .unzip();
+            Ok(PyOffsets::WithBodyOffsets(end_offsets, body_offsets))
+        }
+        (false, true) => {
+            let (end_offsets, times) = read_offsets_with_times(file_path, offset, limit, &options)
+                .map_err(to_py_err)?
+                .into_iter()
+                .unzip();
+            Ok(PyOffsets::WithTimes(end_offsets, times))
+        }
+        (true, true) => Err(PyValueError::new_err(
+            "Body offsets and times cannot be returned together",
+        )),
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, case_insensitive_headers=false))]
+fn build_index(file_path: &str, case_insensitive_headers: bool) -> PyResult<usize> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    Ok(build_frame_index(file_path, &options)
+        .map_err(to_py_err)?
+        .len())
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, end_offsets, times, case_insensitive_headers=false))]
+fn save_index(
+    file_path: &str,
+    end_offsets: Vec<u64>,
+    times: Vec<u64>,
+    case_insensitive_headers: bool,
+) -> PyResult<()> {
+    if end_offsets.len() != times.len() {
+        return Err(PyValueError::new_err(format!(
+            "Got {} end offsets, but {} times",
+            end_offsets.len(),
+            times.len()
+        )));
+    }
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    let starts = std::iter::once(0).chain(end_offsets.iter().copied());
+    let entries = starts
+        .zip(end_offsets.iter().zip(&times))
+        .map(|(start, (&end, &time))| IndexEntry { start, end, time })
+        .collect::<Vec<_>>();
+    save_frame_index(file_path, &entries, &options).map_err(to_py_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, case_insensitive_headers=false))]
+fn load_index(file_path: &str, case_insensitive_headers: bool) -> PyResult<Option<Vec<u64>>> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    Ok(load_frame_index(file_path, &options)
+        .map_err(to_py_err)?
+        .map(|entries| entries.iter().map(|entry| entry.end).collect()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, dry_run=false, case_insensitive_headers=false))]
+fn truncate_incomplete(
+    py: Python<'_>,
+    file_path: &str,
+    dry_run: bool,
+    case_insensitive_headers: bool,
+) -> PyResult<Option<u64>> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    py.detach(|| truncate_incomplete_frame(file_path, dry_run, &options))
+        .map_err(to_py_err)
+}
+
+#[pyfunction]
+fn set_num_threads(n: usize) -> PyResult<()> {
+    set_thread_count(n).map_err(to_py_err)
+}
+
+#[pyfunction]
+fn get_num_threads() -> usize {
+    thread_count()
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, limit, case_insensitive_headers=false, require_frame_start=false))]
+fn read_indicies_mmap(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    case_insensitive_headers: bool,
+    require_frame_start: bool,
+) -> PyResult<Vec<u64>> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        require_frame_start,
+        ..Default::default()
+    };
+    read_offsets_mmap(file_path, offset, limit, &options).map_err(to_py_err)
+}
+
+fn vec3_array<'py>(py: Python<'py>, rows: Vec<[f64; 3]>) -> Bound<'py, PyArray2<f64>> {
+    let n_rows = rows.len();
+    let values = rows.into_iter().flatten().collect::<Vec<f64>>();
+    let array = numpy::ndarray::Array2::from_shape_vec((n_rows, 3), values)
+        .expect("rows of 3 values always fit (n_rows, 3)");
+    PyArray2::from_owned_array(py, array)
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, limit, masses=None))]
+fn centers_of_mass<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    masses: Option<Vec<f64>>,
+) -> PyResult<TimeSeries<'py, PyArray2<f64>>> {
+    let (times, centers): (Vec<u64>, Vec<[f64; 3]>) =
+        read_centers_of_mass(file_path, offset, limit, masses.as_deref())
+            .map_err(to_py_err)?
+            .into_iter()
+            .unzip();
+    Ok((PyArray1::from_vec(py, times), vec3_array(py, centers)))
+}
+
+#[pyfunction]
+fn read_position_bounds<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+) -> PyResult<TimeSeries<'py, PyArray3<f64>>> {
+    let (times, bounds): (Vec<u64>, Vec<Bounds>) = read_bounds(file_path, offset, limit)
+        .map_err(to_py_err)?
+        .into_iter()
+        .unzip();
+    let n_frames = bounds.len();
+    let values = bounds.into_iter().flatten().flatten().collect::<Vec<f64>>();
+    let array = numpy::ndarray::Array3::from_shape_vec((n_frames, 2, 3), values)
+        .expect("bounds of 2x3 values always fit (n_frames, 2, 3)");
+    Ok((
+        PyArray1::from_vec(py, times),
+        PyArray3::from_owned_array(py, array),
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, limit, return_rotations=false))]
+fn oriented_bbox<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    return_rotations: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let (extents, rotations): (Vec<[f64; 3]>, Vec<Matrix3>) =
+        read_oriented_bounds(file_path, offset, limit)
+            .map_err(to_py_err)?
+            .into_iter()
+            .unzip();
+    let extents = vec3_array(py, extents);
+    if !return_rotations {
+        return Ok(extents.into_any());
+    }
+    let n_frames = rotations.len();
+    let values = rotations
+        .into_iter()
+        .flatten()
+        .flatten()
+        .collect::<Vec<f64>>();
+    let array = numpy::ndarray::Array3::from_shape_vec((n_frames, 3, 3), values)
+        .expect("matrices of 3x3 values always fit (n_frames, 3, 3)");
+    Ok((extents, PyArray3::from_owned_array(py, array))
+        .into_pyobject(py)?
+        .into_any())
+}
+
+#[derive(FromPyObject)]
+enum StrandLayout {
+    TopologyPath(String),
+    Lengths(Vec<usize>),
+}
+
+#[pyfunction]
+fn strand_centers<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    top_or_lengths: StrandLayout,
+    offset: u64,
+    limit: usize,
+) -> PyResult<Bound<'py, PyArray3<f64>>> {
+    let strand_lengths = match top_or_lengths {
+        StrandLayout::TopologyPath(topology_path) => read_topology_file(&topology_path)
+            .and_then(|topology| topology.strand_lengths())
+            .map_err(to_py_err)?,
+        StrandLayout::Lengths(lengths) => lengths,
+    };
+    let centers =
+        read_strand_centers(file_path, &strand_lengths, offset, limit).map_err(to_py_err)?;
+    let shape = (centers.len(), strand_lengths.len(), 3);
+    let values = centers
+        .into_iter()
+        .flatten()
+        .flatten()
+        .collect::<Vec<f64>>();
+    let array = numpy::ndarray::Array3::from_shape_vec(shape, values)
+        .expect("centers of each strand always fit (n_frames, n_strands, 3)");
+    Ok(PyArray3::from_owned_array(py, array))
+}
+
+#[pyfunction]
+fn read_rmsd<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    reference: Bound<'py, PyArray2<f64>>,
+) -> PyResult<TimeSeries<'py, PyArray1<f64>>> {
+    let reference = reference.readonly();
+    let reference = reference.as_array();
+    if reference.ncols() < 3 {
+        return Err(PyValueError::new_err(format!(
+            "Reference positions need at least 3 columns, got {}",
+            reference.ncols()
+        )));
+    }
+    let positions = reference
+        .rows()
+        .into_iter()
+        .map(|row| [row[0], row[1], row[2]])
+        .collect::<Vec<_>>();
+    let (times, rmsds): (Vec<u64>, Vec<f64>) = read_rmsds(file_path, offset, limit, &positions)
+        .map_err(to_py_err)?
+        .into_iter()
+        .unzip();
+    Ok((PyArray1::from_vec(py, times), PyArray1::from_vec(py, rmsds)))
+}
+
+#[pyfunction]
+fn end_to_end_vectors<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    first: usize,
+    last: usize,
+) -> PyResult<TimeSeries<'py, PyArray2<f64>>> {
+    let (times, vectors): (Vec<u64>, Vec<[f64; 3]>) =
+        read_end_to_end_vectors(file_path, offset, limit, first, last)
+            .map_err(to_py_err)?
+            .into_iter()
+            .unzip();
+    Ok((PyArray1::from_vec(py, times), vec3_array(py, vectors)))
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, limit, minimum_image=false))]
+fn read_displacements<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    minimum_image: bool,
+) -> PyResult<Bound<'py, PyArray2<f64>>> {
+    let (n_nucleotides, rows) =
+        read_displacement_rows(file_path, offset, limit, minimum_image).map_err(to_py_err)?;
+    let n_rows = rows.len();
+    let values = rows.into_iter().flatten().collect::<Vec<f64>>();
+    let array = numpy::ndarray::Array2::from_shape_vec((n_rows, n_nucleotides), values)
+        .expect("rows are checked to have the same number of nucleotides");
+    Ok(PyArray2::from_owned_array(py, array))
+}
+
+#[pyfunction]
+fn read_pair_distances<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    pairs: Vec<(usize, usize)>,
+) -> PyResult<Bound<'py, PyArray2<f64>>> {
+    let rows = read_pair_distance_rows(file_path, offset, limit, &pairs).map_err(to_py_err)?;
+    let n_rows = rows.len();
+    let values = rows.into_iter().flatten().collect::<Vec<f64>>();
+    let array = numpy::ndarray::Array2::from_shape_vec((n_rows, pairs.len()), values)
+        .expect("rows have one distance per pair");
+    Ok(PyArray2::from_owned_array(py, array))
+}
+
+#[pyfunction]
+fn clash_count<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    radius: f64,
+) -> PyResult<TimeSeries<'py, PyArray1<u64>>> {
+    let (times, counts): (Vec<u64>, Vec<u64>) = read_clash_counts(file_path, offset, limit, radius)
+        .map_err(to_py_err)?
+        .into_iter()
+        .unzip();
+    Ok((
+        PyArray1::from_vec(py, times),
+        PyArray1::from_vec(py, counts),
+    ))
+}
+
+type PyMeanVariance<'py> = (Bound<'py, PyArray2<f64>>, Bound<'py, PyArray2<f64>>);
+
+#[pyfunction]
+fn read_position_statistics<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+) -> PyResult<PyMeanVariance<'py>> {
+    let (mean, variance) =
+        read_position_mean_variance(file_path, offset, limit).map_err(to_py_err)?;
+    Ok((vec3_array(py, mean), vec3_array(py, variance)))
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, limit, masses=None))]
+fn temperature<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    masses: Option<Vec<f64>>,
+) -> PyResult<TimeSeries<'py, PyArray1<f64>>> {
+    let (times, temperatures): (Vec<u64>, Vec<f64>) =
+        read_temperatures(file_path, offset, limit, masses.as_deref())
+            .map_err(to_py_err)?
+            .into_iter()
+            .unzip();
+    Ok((
+        PyArray1::from_vec(py, times),
+        PyArray1::from_vec(py, temperatures),
+    ))
+}
+
+#[pyfunction]
+fn p2_order<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    axis: [f64; 3],
+) -> PyResult<Bound<'py, PyArray1<f64>>> {
+    let order = read_p2_order(file_path, offset, limit, axis).map_err(to_py_err)?;
+    Ok(PyArray1::from_vec(py, order))
+}
+
+#[pyfunction]
+fn equilibration_frame(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    window: usize,
+    tol: f64,
+) -> PyResult<Option<usize>> {
+    find_equilibration(file_path, offset, limit, window, tol).map_err(to_py_err)
+}
+
+#[pyfunction]
+fn check_energy_sum(file_path: &str, offset: u64, limit: usize, tol: f64) -> PyResult<Vec<usize>> {
+    read_energy_mismatches(file_path, offset, limit, tol).map_err(to_py_err)
+}
+
+#[pyfunction]
+fn find_out_of_box(file_path: &str, offset: u64, limit: usize) -> PyResult<Vec<(usize, usize)>> {
+    read_out_of_box(file_path, offset, limit).map_err(to_py_err)
+}
+
+#[pyfunction]
+fn count_mode(file_path: &str, offset: u64) -> PyResult<(usize, Vec<(usize, usize)>)> {
+    read_nucleotide_count_mode(file_path, offset).map_err(to_py_err)
+}
+
+#[pyfunction]
+fn orientation_norm_range(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+) -> PyResult<(f64, f64, f64, f64)> {
+    let [a1_min, a1_max, a3_min, a3_max] =
+        read_orientation_norm_range(file_path, offset, limit).map_err(to_py_err)?;
+    Ok((a1_min, a1_max, a3_min, a3_max))
+}
+
+#[derive(IntoPyObject)]
+enum PyEnergies<'py> {
+    Values(Vec<Bound<'py, PyArray1<f64>>>),
+    Raw(Vec<String>),
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, limit, case_insensitive_headers=false, raw_energy=false))]
+fn read_energies<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    case_insensitive_headers: bool,
+    raw_energy: bool,
+) -> PyResult<PyEnergies<'py>> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    if raw_energy {
+        return Ok(PyEnergies::Raw(
+            read_energy_strings(file_path, offset, limit, &options).map_err(to_py_err)?,
+        ));
+    }
+    let energies = read_energy_values(file_path, offset, limit, &options).map_err(to_py_err)?;
+    Ok(PyEnergies::Values(
+        energies
+            .into_iter()
+            .map(|energy| PyArray1::from_vec(py, energy))
+            .collect(),
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, limit, expected, case_insensitive_headers=false))]
+fn verify_checksums(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    expected: Vec<u32>,
+    case_insensitive_headers: bool,
+) -> PyResult<Vec<usize>> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    let checksums = read_frame_checksums(file_path, offset, limit, &options).map_err(to_py_err)?;
+    if checksums.len() != expected.len() {
+        return Err(PyValueError::new_err(format!(
+            "Found {} configurations, expected {} checksums",
+            checksums.len(),
+            expected.len()
+        )));
+    }
+    Ok(checksums
+        .iter()
+        .zip(&expected)
+        .enumerate()
+        .filter(|(_, (checksum, expected))| checksum != expected)
+        .map(|(index, _)| index)
+        .collect())
+}
+
+// times are unsigned, so a difference of times far apart may not fit i64
+pub fn time_differences(times: &[u64]) -> Result<Vec<i64>, Error> {
+    times
+        .windows(2)
+        .map(|pair| {
+            i64::try_from(pair[1] as i128 - pair[0] as i128).map_err(|_| {
+                Error::new(
+                    InvalidInput,
+                    format!(
+                        "Difference between times {} and {} does not fit a 64-bit signed integer",
+                        pair[0], pair[1]
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, case_insensitive_headers=false))]
+fn time_deltas<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    case_insensitive_headers: bool,
+) -> PyResult<Bound<'py, PyArray1<i64>>> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    let times = read_times(file_path, offset, usize::MAX, &options).map_err(to_py_err)?;
+    let deltas = time_differences(&times).map_err(to_py_err)?;
+    Ok(PyArray1::from_vec(py, deltas))
+}
+
+type PyBoxSeries<'py> = (Bound<'py, PyArray1<u64>>, Bound<'py, PyArray2<f64>>);
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, limit, case_insensitive_headers=false))]
+fn read_boxes<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    case_insensitive_headers: bool,
+) -> PyResult<PyBoxSeries<'py>> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    let series = read_box_series(file_path, offset, limit, &options).map_err(to_py_err)?;
+    let (times, boxes): (Vec<u64>, Vec<[f64; 3]>) = series.into_iter().unzip();
+    let array = numpy::ndarray::Array2::from(boxes);
+    Ok((
+        PyArray1::from_vec(py, times),
+        PyArray2::from_owned_array(py, array),
+    ))
+}
+
+type PyHeaderSeries<'py> = (
+    Bound<'py, PyArray1<u64>>,
+    Bound<'py, PyArray2<f64>>,
+    Bound<'py, PyArray2<f64>>,
+);
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, limit, case_insensitive_headers=false))]
+fn read_headers<'py>(
+    py: Python<'py>,
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    case_insensitive_headers: bool,
+) -> PyResult<PyHeaderSeries<'py>> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    let headers = py
+        .detach(|| read_header_series(file_path, offset, limit, &options))
+        .map_err(to_py_err)?;
+    let n_frames = headers.len();
+    let n_energies = headers.first().map_or(0, |(_, _, energy)| energy.len());
+    let mut times = Vec::with_capacity(n_frames);
+    let mut boxes = Vec::with_capacity(n_frames * 3);
+    let mut energies = Vec::with_capacity(n_frames * n_energies);
+    for (index, (time, cbox, energy)) in headers.into_iter().enumerate() {
+        if energy.len() != n_energies {
+            return Err(PyValueError::new_err(format!(
+                "Configuration {} at time {} has {} energy values, expected {}",
+                index,
+                time,
+                energy.len(),
+                n_energies
+            )));
+        }
+        times.push(time);
+        boxes.extend(cbox);
+        energies.extend(energy);
+    }
+    let boxes = numpy::ndarray::Array2::from_shape_vec((n_frames, 3), boxes)
+        .expect("boxes are parsed as 3 values");
+    let energies = numpy::ndarray::Array2::from_shape_vec((n_frames, n_energies), energies)
+        .expect("energy counts are checked to match");
+    Ok((
+        PyArray1::from_vec(py, times),
+        PyArray2::from_owned_array(py, boxes),
+        PyArray2::from_owned_array(py, energies),
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset, limit, case_insensitive_headers=false, topology_path=None))]
+fn validate_trajectory(
+    file_path: &str,
+    offset: u64,
+    limit: usize,
+    case_insensitive_headers: bool,
+    topology_path: Option<&str>,
+) -> PyResult<ValidationSummary> {
+    let options = ParseOptions {
+        case_insensitive_headers,
+        ..Default::default()
+    };
+    let expected_nucleotides = match topology_path {
+        Some(topology_path) => Some(
+            read_topology_file(topology_path)
+                .map_err(to_py_err)?
+                .strand_ids
+                .len(),
+        ),
+        None => None,
+    };
+    validate_frames(file_path, offset, limit, expected_nucleotides, &options).map_err(to_py_err)
+}
+
+type PyTopology<'py> = (
+    Bound<'py, PyArray1<i64>>,
+    Vec<String>,
+    Bound<'py, PyArray2<i64>>,
+    Vec<bool>,
+);
+
+#[pyfunction]
+fn read_topology<'py>(py: Python<'py>, file_path: &str) -> PyResult<PyTopology<'py>> {
+    let topology = read_topology_file(file_path).map_err(to_py_err)?;
+    let neighbors = numpy::ndarray::Array2::from(topology.neighbors);
+    Ok((
+        PyArray1::from_vec(py, topology.strand_ids),
+        topology.bases,
+        PyArray2::from_owned_array(py, neighbors),
+        topology.circular,
+    ))
+}
+
+#[pyclass]
+struct FrameView {
+    #[pyo3(get)]
+    file_path: String,
+    #[pyo3(get)]
+    offset: u64,
+    case_insensitive_headers: bool,
+    configuration: Option<Configuration>,
+}
+
+impl FrameView {
+    fn configuration(&mut self) -> Result<&Configuration, Error> {
+        if self.configuration.is_none() {
+            let options = ParseOptions {
+                case_insensitive_headers: self.case_insensitive_headers,
+                ..Default::default()
+            };
+            let (_, conf) = read_confs(&self.file_path, self.offset, 1, &options)?
+                .pop()
+                .ok_or_else(|| {
+                    Error::new(
+                        InvalidInput,
+                        format!(
+                            "No configuration found at offset {} of \"{}\"",
+                            self.offset, self.file_path
+                        ),
+                    )
+                })?;
+            self.configuration = Some(conf);
+        }
+        Ok(self.configuration.as_ref().unwrap())
+    }
+}
+
+#[pymethods]
+impl FrameView {
+    #[new]
+    #[pyo3(signature = (file_path, offset, case_insensitive_headers=false))]
+    fn new(file_path: String, offset: u64, case_insensitive_headers: bool) -> Self {
+        Self {
+            file_path,
+            offset,
+            case_insensitive_headers,
+            configuration: None,
+        }
+    }
+
+    #[getter]
+    fn is_parsed(&self) -> bool {
+        self.configuration.is_some()
+    }
+
+    #[getter]
+    fn time(&mut self) -> PyResult<u64> {
+        Ok(self.configuration().map_err(to_py_err)?.time)
+    }
+
+    #[getter(r#box)]
+    fn cbox<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        Ok(PyArray1::from_slice(
+            py,
+            &self.configuration().map_err(to_py_err)?.cbox,
+        ))
+    }
+
+    #[getter]
+    fn positions<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<f64>>> {
+        let positions = self
+            .configuration()
+            .map_err(to_py_err)?
+            .nucleotides
+            .iter()
+            .map(|nucleotide| nucleotide[..3].to_vec())
+            .collect::<Vec<_>>();
+        Ok(PyArray2::from_vec2(py, &positions)?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<FrameView file_path=\"{}\" offset={} parsed={}>",
+            self.file_path,
+            self.offset,
+            self.is_parsed()
+        )
+    }
+}
+
+#[pyclass]
+struct TrajectoryReader {
+    frames: ConfigReader,
+    options: ParseOptions,
+    index: usize,
+    reuse_buffer: bool,
+    buffer: Option<Py<PyArray2<f64>>>,
+}
+
+// copies nucleotides into the rows of an array of the same shape, false leaving it untouched otherwise
+fn fill_rows(mut array: numpy::ndarray::ArrayViewMut2<f64>, nucleotides: &[Vec<f64>]) -> bool {
+    let n_columns = nucleotides.first().map_or(0, Vec::len);
+    if array.shape() != [nucleotides.len(), n_columns] {
+        return false;
+    }
+    for (mut row, values) in array.rows_mut().into_iter().zip(nucleotides) {
+        row.assign(&numpy::ndarray::ArrayView1::from(values.as_slice()));
+    }
+    true
+}
+
+impl TrajectoryReader {
+    fn next_frame(&mut self) -> Result<Option<(usize, u64, Configuration)>, Error> {
+        let Some(result) = self.frames.next() else {
+            return Ok(None);
+        };
+        let (config_start, _, lines) = result?;
+        let conf = Configuration::from_lines(lines, config_start, &self.options).map_err(|e| {
+            self.frames
+                .with_line_number(TrajectoryError::at_index(e, self.index))
+        })?;
+        self.index += 1;
+        Ok(Some((self.index - 1, config_start, conf)))
+    }
+
+    fn next_py_frame<'py>(
+        &mut self,
+        py: Python<'py>,
+    ) -> PyResult<Option<(usize, u64, PyConfiguration<'py>)>> {
+        let Some((index, config_start, conf)) = self.next_frame().map_err(to_py_err)? else {
+            return Ok(None);
+        };
+        if !self.reuse_buffer {
+            return Ok(Some((index, config_start, conf.into_py_configuration(py)?)));
+        }
+        // overwrite the previous frame's array if it has the same shape and is still writeable
+        let reused = self
+            .buffer
+            .as_ref()
+            .map(|buffer| buffer.bind(py).clone())
+            .filter(|buffer| {
+                buffer
+                    .try_readwrite()
+                    .is_ok_and(|mut array| fill_rows(array.as_array_mut(), &conf.nucleotides))
+            });
+        let np_nucleotides = match reused {
+            Some(buffer) => buffer,
+            None => {
+                let buffer = PyArray2::from_vec2(py, &conf.nucleotides)?;
+                self.buffer = Some(buffer.clone().unbind());
+                buffer
+            }
+        };
+        let np_box = PyArray1::from_vec(py, conf.cbox);
+        let np_energy = PyArray1::from_vec(py, conf.cenergy);
+        Ok(Some((
+            index,
+            config_start,
+            (conf.time, np_box, np_energy, np_nucleotides),
+        )))
+    }
+}
+
+#[pymethods]
+impl TrajectoryReader {
+    #[new]
+    #[pyo3(signature = (file_path, offset=0, case_insensitive_headers=false, reuse_buffer=false))]
+    fn new(
+        file_path: &str,
+        offset: u64,
+        case_insensitive_headers: bool,
+        reuse_buffer: bool,
+    ) -> PyResult<Self> {
+        let options = ParseOptions {
+            case_insensitive_headers,
+            ..Default::default()
+        };
+        Ok(Self {
+            frames: ConfigReader::new(file_path, offset, SaveLines::All, &options)
+                .map_err(to_py_err)?,
+            options,
+            index: 0,
+            reuse_buffer,
+            buffer: None,
+        })
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&mut self, py: Python<'py>) -> PyResult<Option<PyConfiguration<'py>>> {
+        Ok(self.next_py_frame(py)?.map(|(_, _, conf)| conf))
+    }
+
+    fn enumerate_with_offsets(slf: Bound<'_, Self>) -> EnumerateWithOffsets {
+        EnumerateWithOffsets {
+            reader: slf.unbind(),
+        }
+    }
+}
+
+#[pyclass]
+struct TrajectoryWriter {
+    // None once closed
+    output: Option<std::io::BufWriter<File>>,
+    #[pyo3(get)]
+    offset: u64,
+}
+
+impl TrajectoryWriter {
+    fn output(&mut self) -> PyResult<&mut std::io::BufWriter<File>> {
+        self.output
+            .as_mut()
+            .ok_or_else(|| PyValueError::new_err("I/O operation on closed TrajectoryWriter"))
+    }
+}
+
+#[pymethods]
+impl TrajectoryWriter {
+    #[new]
+    #[pyo3(signature = (file_path, append=false))]
+    fn new(file_path: &str, append: bool) -> PyResult<Self> {
+        let (file, offset) = open_output(file_path, append).map_err(to_py_err)?;
+        Ok(Self {
+            output: Some(std::io::BufWriter::new(file)),
+            offset,
+        })
+    }
+
+    fn write(
+        &mut self,
+        time: u64,
+        r#box: Bound<'_, PyArray1<f64>>,
+        energy: Bound<'_, PyArray1<f64>>,
+        nucleotides: Bound<'_, PyArray2<f64>>,
+    ) -> PyResult<u64> {
+        let serialized = dumps_conf(
+            time,
+            r#box.readonly().as_array(),
+            energy.readonly().as_array(),
+            nucleotides.readonly().as_array(),
+            None,
+        )
+        .map_err(to_py_err)?;
+        self.output()?
+            .write_all(serialized.as_bytes())
+            .map_err(to_py_err)?;
+        self.offset += serialized.len() as u64;
+        Ok(self.offset)
+    }
+
+    fn flush(&mut self) -> PyResult<()> {
+        self.output()?.flush().map_err(to_py_err)
+    }
+
+    fn close(&mut self) -> PyResult<()> {
+        match self.output.take() {
+            Some(mut output) => output.flush().map_err(to_py_err),
+            None => Ok(()),
+        }
+    }
+
+    #[getter]
+    fn closed(&self) -> bool {
+        self.output.is_none()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<Bound<'_, PyAny>>,
+        _exc_value: Option<Bound<'_, PyAny>>,
+        _traceback: Option<Bound<'_, PyAny>>,
+    ) -> PyResult<()> {
+        self.close()
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, offset=0, case_insensitive_headers=false))]
+fn iter_configurations(
+    file_path: &str,
+    offset: u64,
+    case_insensitive_headers: bool,
+) -> PyResult<TrajectoryReader> {
+    TrajectoryReader::new(file_path, offset, case_insensitive_headers, false)
+}
+
+#[pyclass]
+struct EnumerateWithOffsets {
+    reader: Py<TrajectoryReader>,
+}
+
+#[pymethods]
+impl EnumerateWithOffsets {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<Option<(usize, u64, PyConfiguration<'py>)>> {
+        self.reader.borrow_mut(py).next_py_frame(py)
+    }
+}
+
+pub fn dumps_conf(
+    time: u64,
+    box_array: numpy::ndarray::ArrayView1<'_, f64>,
+    energy_array: numpy::ndarray::ArrayView1<'_, f64>,
+    nucleotides_array: numpy::ndarray::ArrayView2<'_, f64>,
+    pad_to: Option<usize>,
+) -> Result<String, Error> {
+    let width = nucleotides_array.ncols();
+    let padding = match pad_to {
+        Some(pad_to) if pad_to < width => {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                "Cannot pad {} nucleotide columns to {}, pad_to must be at least the current width",
+                width, pad_to
+            ),
+            ))
+        }
+        // zeros for the missing columns, e.g. velocities of a 9 column frame
+        Some(pad_to) => " 0".repeat(pad_to - width),
+        None => String::new(),
+    };
+    let box_values = box_array
+        .iter()
+        .map(|x| x.to_string())
         .collect::<Vec<_>>()
         .join(" ");
     let energy_values = energy_array
@@ -295,71 +5078,2235 @@ pub fn dumps_conf(
         .collect::<Vec<_>>()
         .join(" ");
 
-    let header = format!("t = {}\nb = {}\nE = {}", time, box_values, energy_values);
+    let header = format!("t = {}\nb = {}\nE = {}", time, box_values, energy_values);
+
+    let mut lines = nucleotides_array
+        .axis_iter(numpy::ndarray::Axis(0))
+        .map(|line| {
+            let mut values = line
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            values.push_str(&padding);
+            values
+        })
+        .collect::<Vec<_>>();
+
+    lines.insert(0, header);
+    lines.push(String::new());
+
+    Ok(lines.join("\n"))
+}
+
+pub fn validate_permutation(permutation: &[usize], rows: usize) -> Result<(), Error> {
+    if permutation.len() != rows {
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "Permutation has {} indices, expected {} nucleotides",
+                permutation.len(),
+                rows
+            ),
+        ));
+    }
+    let mut seen = vec![false; rows];
+    for &index in permutation {
+        if index >= rows || seen[index] {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Invalid permutation index {}, expected each of 0..{} exactly once",
+                    index, rows
+                ),
+            ));
+        }
+        seen[index] = true;
+    }
+    Ok(())
+}
+
+// fold positions into the primary box [0, box), orientation and velocity columns are kept
+pub fn wrap_positions(
+    mut nucleotides_array: numpy::ndarray::ArrayViewMut2<'_, f64>,
+    box_array: numpy::ndarray::ArrayView1<'_, f64>,
+) -> Result<(), Error> {
+    if box_array.len() < 3 {
+        return Err(Error::new(
+            InvalidInput,
+            format!("Box array needs 3 values, got {}", box_array.len()),
+        ));
+    }
+    if nucleotides_array.ncols() < 3 {
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "Nucleotide array needs at least 3 position columns, got {}",
+                nucleotides_array.ncols()
+            ),
+        ));
+    }
+    for mut row in nucleotides_array.rows_mut() {
+        for axis in 0..3 {
+            row[axis] -= box_array[axis] * (row[axis] / box_array[axis]).floor();
+        }
+    }
+    Ok(())
+}
+
+// nucleotides reordered by permutation, then wrapped into the box, before serializing
+fn dumps_transformed_conf(
+    time: u64,
+    box_array: numpy::ndarray::ArrayView1<'_, f64>,
+    energy_array: numpy::ndarray::ArrayView1<'_, f64>,
+    nucleotides_array: numpy::ndarray::ArrayView2<'_, f64>,
+    permutation: Option<&[usize]>,
+    wrap: bool,
+    pad_to: Option<usize>,
+) -> Result<String, Error> {
+    let mut transformed = None;
+    if let Some(permutation) = permutation {
+        validate_permutation(permutation, nucleotides_array.nrows())?;
+        transformed = Some(nucleotides_array.select(numpy::ndarray::Axis(0), permutation));
+    }
+    if wrap {
+        let wrapped = transformed.get_or_insert_with(|| nucleotides_array.to_owned());
+        wrap_positions(wrapped.view_mut(), box_array)?;
+    }
+    let nucleotides_array = transformed
+        .as_ref()
+        .map_or(nucleotides_array.view(), |transformed| transformed.view());
+    dumps_conf(time, box_array, energy_array, nucleotides_array, pad_to)
+}
+
+#[pyfunction]
+#[pyo3(signature = (configs, permutation=None, pad_to=None, wrap=false))]
+fn dumps_configurations(
+    configs: &Bound<'_, PyAny>,
+    permutation: Option<Vec<usize>>,
+    pad_to: Option<usize>,
+    wrap: bool,
+) -> PyResult<Vec<String>> {
+    let configs: Vec<PyConfiguration<'_>> = configs.extract()?;
+
+    let refs = configs
+        .into_iter()
+        .map(|(time, np_box, np_energe, np_nucleotides)| {
+            (
+                time,
+                np_box.readonly(),
+                np_energe.readonly(),
+                np_nucleotides.readonly(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let arrays = refs
+        .iter()
+        .map(|(time, box_ref, energy_ref, nucleotides_ref)| {
+            (
+                *time,
+                (*box_ref).as_array(),
+                (*energy_ref).as_array(),
+                (*nucleotides_ref).as_array(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let serialized = in_thread_pool(|| {
+        arrays
+            .par_iter()
+            .map(|(time, box_array, energy_array, nucleotides_array)| {
+                dumps_transformed_conf(
+                    *time,
+                    *box_array,
+                    *energy_array,
+                    *nucleotides_array,
+                    permutation.as_deref(),
+                    wrap,
+                    pad_to,
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()
+    })
+    .map_err(to_py_err)?;
+
+    Ok(serialized)
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, configs, append=false))]
+fn write_configurations(
+    file_path: &str,
+    configs: &Bound<'_, PyAny>,
+    append: bool,
+) -> PyResult<Vec<u64>> {
+    let configs: Vec<PyConfiguration<'_>> = configs.extract()?;
+
+    let refs = configs
+        .into_iter()
+        .map(|(time, np_box, np_energe, np_nucleotides)| {
+            (
+                time,
+                np_box.readonly(),
+                np_energe.readonly(),
+                np_nucleotides.readonly(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let arrays = refs
+        .iter()
+        .map(|(time, box_ref, energy_ref, nucleotides_ref)| {
+            (
+                *time,
+                (*box_ref).as_array(),
+                (*energy_ref).as_array(),
+                (*nucleotides_ref).as_array(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    write_confs(file_path, &arrays, append).map_err(to_py_err)
+}
+
+#[pyfunction]
+fn dumps_configurations_positions(configs: &Bound<'_, PyAny>) -> PyResult<Vec<String>> {
+    let configs: Vec<PyConfiguration<'_>> = configs.extract()?;
+
+    let refs = configs
+        .into_iter()
+        .map(|(time, np_box, np_energe, np_nucleotides)| {
+            (
+                time,
+                np_box.readonly(),
+                np_energe.readonly(),
+                np_nucleotides.readonly(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let arrays = refs
+        .iter()
+        .map(|(time, box_ref, energy_ref, nucleotides_ref)| {
+            (
+                *time,
+                (*box_ref).as_array(),
+                (*energy_ref).as_array(),
+                (*nucleotides_ref).as_array(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    in_thread_pool(|| {
+        arrays
+            .par_iter()
+            .map(|(time, box_array, energy_array, nucleotides_array)| {
+                if nucleotides_array.ncols() < 3 {
+                    return Err(Error::new(
+                        InvalidInput,
+                        format!(
+                            "Nucleotide array needs at least 3 position columns, got {}",
+                            nucleotides_array.ncols()
+                        ),
+                    ));
+                }
+                // only position columns are formatted
+                let positions = nucleotides_array.slice(numpy::ndarray::s![.., ..3]);
+                dumps_conf(*time, *box_array, *energy_array, positions, None)
+            })
+            .collect::<Result<Vec<_>, Error>>()
+    })
+    .map_err(to_py_err)
+}
+
+pub fn append_conf(
+    file_path: &str,
+    time: u64,
+    box_array: numpy::ndarray::ArrayView1<'_, f64>,
+    energy_array: numpy::ndarray::ArrayView1<'_, f64>,
+    nucleotides_array: numpy::ndarray::ArrayView2<'_, f64>,
+) -> Result<u64, Error> {
+    let serialized = dumps_conf(time, box_array, energy_array, nucleotides_array, None)?;
+    let (mut file, offset) = open_output(file_path, true)?;
+    file.write_all(serialized.as_bytes())?;
+    Ok(offset + serialized.len() as u64)
+}
+
+// returns the file with the offset where the next configuration starts,
+// an appended file not ending with a newline gets one
+fn open_output(file_path: &str, append: bool) -> Result<(File, u64), Error> {
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .append(append)
+        .truncate(!append)
+        .open(file_path)?;
+    let mut offset = file.metadata()?.len();
+    if offset > 0 {
+        let mut last_byte = [0u8];
+        file.seek(SeekFrom::Start(offset - 1))?;
+        file.read_exact(&mut last_byte)?;
+        if last_byte[0] != b'\n' {
+            file.write_all(b"\n")?;
+            offset += 1;
+        }
+    }
+    Ok((file, offset))
+}
+
+type ConfArrays<'a> = (
+    u64,
+    numpy::ndarray::ArrayView1<'a, f64>,
+    numpy::ndarray::ArrayView1<'a, f64>,
+    numpy::ndarray::ArrayView2<'a, f64>,
+);
+
+// serialized FRAME_CHUNK configurations at a time in parallel, so only one chunk of text is held,
+// returns end offsets of written configurations like read_offsets
+pub fn write_confs(
+    file_path: &str,
+    confs: &[ConfArrays<'_>],
+    append: bool,
+) -> Result<Vec<u64>, Error> {
+    let (file, mut offset) = open_output(file_path, append)?;
+    let mut output = std::io::BufWriter::new(file);
+    let mut end_offsets = Vec::with_capacity(confs.len());
+    for chunk in confs.chunks(FRAME_CHUNK) {
+        let serialized = in_thread_pool(|| {
+            chunk
+                .par_iter()
+                .map(|(time, box_array, energy_array, nucleotides_array)| {
+                    dumps_conf(*time, *box_array, *energy_array, *nucleotides_array, None)
+                })
+                .collect::<Result<Vec<_>, Error>>()
+        })?;
+        for conf in serialized {
+            output.write_all(conf.as_bytes())?;
+            offset += conf.len() as u64;
+            end_offsets.push(offset);
+        }
+    }
+    output.flush()?;
+    Ok(end_offsets)
+}
+
+pub fn write_csv(input_path: &str, output_path: &str) -> Result<usize, Error> {
+    let options = ParseOptions::default();
+    let reader = ConfigReader::new(input_path, 0, SaveLines::All, &options)?;
+    let mut output = std::io::BufWriter::new(File::create(output_path)?);
+    writeln!(output, "frame,time,nucleotide,x,y,z")?;
+    let mut written = 0;
+    for (frame, result) in reader.enumerate() {
+        let (_, _, lines) = result?;
+        let (time, _, _) = Configuration::parse_headers(&lines, &options)?;
+        for (index, line) in lines[3..].iter().enumerate() {
+            let nucleotide = Configuration::parse_nucleotide(line)?;
+            writeln!(
+                output,
+                "{},{},{},{},{},{}",
+                frame, time, index, nucleotide[0], nucleotide[1], nucleotide[2]
+            )?;
+        }
+        written += 1;
+    }
+    output.flush()?;
+    Ok(written)
+}
+
+#[pyfunction]
+fn write_positions_csv(in_path: &str, out_path: &str) -> PyResult<usize> {
+    write_csv(in_path, out_path).map_err(to_py_err)
+}
+
+// one record per nucleotide labelled with its base, or per strand at the centroid of its nucleotide positions
+// labelled S and its strand id, without unwrapping periodic boundaries
+pub fn write_xyz(
+    input_path: &str,
+    output_path: &str,
+    topology_path: &str,
+    offset: u64,
+    limit: usize,
+    strand_centers: bool,
+) -> Result<usize, Error> {
+    let topology = read_topology_file(topology_path)?;
+    let mut strand_indices = std::collections::HashMap::new();
+    let mut strand_labels = Vec::new();
+    // strands in order of their first nucleotide
+    let strand_of = topology
+        .strand_ids
+        .iter()
+        .map(|&strand_id| {
+            *strand_indices.entry(strand_id).or_insert_with(|| {
+                strand_labels.push(format!("S{}", strand_id));
+                strand_labels.len() - 1
+            })
+        })
+        .collect::<Vec<_>>();
+    let options = ParseOptions::default();
+    let mut frames = ConfigReader::new(input_path, offset, SaveLines::All, &options)?.take(limit);
+    let mut output = std::io::BufWriter::new(File::create(output_path)?);
+    let mut written = 0;
+    loop {
+        let configs = parse_next_chunk(&mut frames, &options)?;
+        if configs.is_empty() {
+            break;
+        }
+        for (_, conf) in configs {
+            if conf.nucleotides.len() != topology.bases.len() {
+                return Err(Error::new(
+                    InvalidInput,
+                    format!(
+                        "Configuration at time {} has {} nucleotides, topology has {}",
+                        conf.time,
+                        conf.nucleotides.len(),
+                        topology.bases.len()
+                    ),
+                ));
+            }
+            let frame = if strand_centers {
+                let mut sums = vec![([0.0; 3], 0usize); strand_labels.len()];
+                for (nucleotide, &strand) in conf.nucleotides.iter().zip(&strand_of) {
+                    for (sum, value) in sums[strand].0.iter_mut().zip(nucleotide) {
+                        *sum += value;
+                    }
+                    sums[strand].1 += 1;
+                }
+                format_xyz(
+                    conf.time,
+                    strand_labels.iter().zip(sums).map(|(label, (sum, count))| {
+                        let count = count as f64;
+                        (
+                            label.as_str(),
+                            [sum[0] / count, sum[1] / count, sum[2] / count],
+                        )
+                    }),
+                )
+            } else {
+                format_xyz(
+                    conf.time,
+                    topology
+                        .bases
+                        .iter()
+                        .zip(&conf.nucleotides)
+                        .map(|(base, nucleotide)| {
+                            (base.as_str(), [nucleotide[0], nucleotide[1], nucleotide[2]])
+                        }),
+                )
+            };
+            output.write_all(frame.as_bytes())?;
+            written += 1;
+        }
+    }
+    output.flush()?;
+    Ok(written)
+}
+
+#[pyfunction]
+#[pyo3(signature = (in_path, out_path, topology_path, offset, limit, strand_centers=false))]
+fn write_configurations_xyz(
+    py: Python<'_>,
+    in_path: &str,
+    out_path: &str,
+    topology_path: &str,
+    offset: u64,
+    limit: usize,
+    strand_centers: bool,
+) -> PyResult<usize> {
+    py.detach(|| {
+        write_xyz(
+            in_path,
+            out_path,
+            topology_path,
+            offset,
+            limit,
+            strand_centers,
+        )
+    })
+    .map_err(to_py_err)
+}
+
+pub fn spill_confs(
+    input_path: &str,
+    output_path: &str,
+    offset: u64,
+    limit: usize,
+) -> Result<(Vec<u64>, usize, usize), Error> {
+    let options = ParseOptions::default();
+    let mut frames = ConfigReader::new(input_path, offset, SaveLines::All, &options)?.take(limit);
+    let mut output = std::io::BufWriter::new(File::create(output_path)?);
+    let mut times = Vec::new();
+    let mut n_nucleotides = None;
+    // detected from the first nucleotide, as configurations may have 9 or 15 columns
+    let mut n_columns = None;
+    loop {
+        let configs = parse_next_chunk(&mut frames, &options)?;
+        if configs.is_empty() {
+            break;
+        }
+        for (_, conf) in configs {
+            let expected = *n_nucleotides.get_or_insert(conf.nucleotides.len());
+            if conf.nucleotides.len() != expected {
+                return Err(Error::new(
+                    InvalidInput,
+                    format!(
+                        "Configuration at time {} has {} nucleotides, expected {}",
+                        conf.time,
+                        conf.nucleotides.len(),
+                        expected
+                    ),
+                ));
+            }
+            for nucleotide in &conf.nucleotides {
+                let expected = *n_columns.get_or_insert(nucleotide.len());
+                if nucleotide.len() != expected {
+                    return Err(Error::new(
+                        InvalidInput,
+                        format!(
+                            "Configuration at time {} has nucleotides with {} columns, expected {}",
+                            conf.time,
+                            nucleotide.len(),
+                            expected
+                        ),
+                    ));
+                }
+                for value in nucleotide {
+                    output.write_all(&value.to_ne_bytes())?;
+                }
+            }
+            times.push(conf.time);
+        }
+    }
+    output.flush()?;
+    Ok((times, n_nucleotides.unwrap_or(0), n_columns.unwrap_or(15)))
+}
+
+#[pyfunction]
+fn read_configurations_mmap_out<'py>(
+    py: Python<'py>,
+    in_path: &str,
+    out_path: &str,
+    offset: u64,
+    limit: usize,
+) -> PyResult<TimeSeries<'py, PyAny>> {
+    let (times, n_nucleotides, n_columns) =
+        spill_confs(in_path, out_path, offset, limit).map_err(to_py_err)?;
+    let shape = (times.len(), n_nucleotides, n_columns);
+    let numpy = py.import("numpy")?;
+    // numpy cannot map an empty file
+    let nucleotides = if times.is_empty() || n_nucleotides == 0 {
+        numpy.call_method1("zeros", (shape,))?
+    } else {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("dtype", "float64")?;
+        kwargs.set_item("mode", "r+")?;
+        kwargs.set_item("shape", shape)?;
+        numpy.call_method("memmap", (out_path,), Some(&kwargs))?
+    };
+    Ok((PyArray1::from_vec(py, times), nucleotides))
+}
+
+#[pyfunction]
+fn downsample_trajectory(input_path: &str, output_path: &str, stride: usize) -> PyResult<usize> {
+    downsample(input_path, output_path, stride).map_err(to_py_err)
+}
+
+#[pyfunction]
+fn merge_trajectories_by_time(path_a: &str, path_b: &str, output_path: &str) -> PyResult<usize> {
+    merge_by_time(path_a, path_b, output_path).map_err(to_py_err)
+}
+
+#[pyfunction]
+fn append_configuration(
+    file_path: &str,
+    time: u64,
+    r#box: Bound<'_, PyArray1<f64>>,
+    energy: Bound<'_, PyArray1<f64>>,
+    nucleotides: Bound<'_, PyArray2<f64>>,
+) -> PyResult<u64> {
+    append_conf(
+        file_path,
+        time,
+        r#box.readonly().as_array(),
+        energy.readonly().as_array(),
+        nucleotides.readonly().as_array(),
+    )
+    .map_err(to_py_err)
+}
+
+// XYZ frame with the record count, a time comment line and one `label x y z` line per record
+fn format_xyz<'a>(
+    time: u64,
+    records: impl ExactSizeIterator<Item = (&'a str, [f64; 3])>,
+) -> String {
+    let mut lines = Vec::with_capacity(records.len() + 3);
+    lines.push(records.len().to_string());
+    lines.push(format!("t = {}", time));
+    lines.extend(records.map(|(label, [x, y, z])| format!("{} {} {} {}", label, x, y, z)));
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+// `labels` holds one label per nucleotide
+pub fn dumps_conf_xyz(
+    time: u64,
+    nucleotides_array: numpy::ndarray::ArrayView2<'_, f64>,
+    labels: &[&str],
+) -> Result<String, Error> {
+    if nucleotides_array.ncols() < 3 {
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "Nucleotide array needs at least 3 position columns, got {}",
+                nucleotides_array.ncols()
+            ),
+        ));
+    }
+    if labels.len() != nucleotides_array.nrows() {
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "Configuration at time {} has {} nucleotides, got {} bases",
+                time,
+                nucleotides_array.nrows(),
+                labels.len()
+            ),
+        ));
+    }
+
+    Ok(format_xyz(
+        time,
+        nucleotides_array
+            .axis_iter(numpy::ndarray::Axis(0))
+            .zip(labels)
+            .map(|(line, label)| (*label, [line[0], line[1], line[2]])),
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (configs, element, bases=None))]
+fn dumps_configurations_xyz(
+    configs: &Bound<'_, PyAny>,
+    element: &str,
+    bases: Option<Vec<String>>,
+) -> PyResult<Vec<String>> {
+    let configs: Vec<PyConfiguration<'_>> = configs.extract()?;
+
+    let refs = configs
+        .into_iter()
+        .map(|(time, _, _, np_nucleotides)| (time, np_nucleotides.readonly()))
+        .collect::<Vec<_>>();
+
+    let arrays = refs
+        .iter()
+        .map(|(time, nucleotides_ref)| (*time, (*nucleotides_ref).as_array()))
+        .collect::<Vec<_>>();
+
+    let bases = bases
+        .as_ref()
+        .map(|bases| bases.iter().map(String::as_str).collect::<Vec<_>>());
+
+    in_thread_pool(|| {
+        arrays
+            .par_iter()
+            .map(|(time, nucleotides_array)| match &bases {
+                Some(bases) => dumps_conf_xyz(*time, *nucleotides_array, bases),
+                None => dumps_conf_xyz(
+                    *time,
+                    *nucleotides_array,
+                    &vec![element; nucleotides_array.nrows()],
+                ),
+            })
+            .collect::<Result<Vec<_>, Error>>()
+    })
+    .map_err(to_py_err)
+}
+
+pub fn dumps_conf_lammps(
+    time: u64,
+    box_array: numpy::ndarray::ArrayView1<'_, f64>,
+    nucleotides_array: numpy::ndarray::ArrayView2<'_, f64>,
+    box_origin: [f64; 3],
+) -> Result<String, Error> {
+    if box_array.len() < 3 {
+        return Err(Error::new(
+            InvalidInput,
+            format!("Box array needs 3 values, got {}", box_array.len()),
+        ));
+    }
+    if nucleotides_array.ncols() < 3 {
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "Nucleotide array needs at least 3 position columns, got {}",
+                nucleotides_array.ncols()
+            ),
+        ));
+    }
+
+    // oxDNA boxes are periodic along every axis
+    let bounds = (0..3)
+        .map(|axis| {
+            format!(
+                "{} {}",
+                box_origin[axis],
+                box_origin[axis] + box_array[axis]
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let header = format!(
+        "ITEM: TIMESTEP\n{}\nITEM: NUMBER OF ATOMS\n{}\nITEM: BOX BOUNDS pp pp pp\n{}\nITEM: ATOMS id x y z",
+        time,
+        nucleotides_array.nrows(),
+        bounds
+    );
 
+    // LAMMPS atom ids start at 1
     let mut lines = nucleotides_array
         .axis_iter(numpy::ndarray::Axis(0))
-        .map(|line| {
-            line.iter()
-                .map(|x| x.to_string())
-                .collect::<Vec<_>>()
-                .join(" ")
-        })
+        .enumerate()
+        .map(|(index, line)| format!("{} {} {} {}", index + 1, line[0], line[1], line[2]))
         .collect::<Vec<_>>();
 
     lines.insert(0, header);
     lines.push(String::new());
 
-    lines.join("\n")
+    Ok(lines.join("\n"))
 }
 
 #[pyfunction]
-fn dumps_configurations(configs: &Bound<'_, PyAny>) -> PyResult<Vec<String>> {
-    let configs: Vec<(
-        u64,
-        Bound<'_, PyArray1<f64>>,
-        Bound<'_, PyArray1<f64>>,
-        Bound<'_, PyArray2<f64>>,
-    )> = configs.extract()?;
+#[pyo3(signature = (configs, box_origin=[0.0, 0.0, 0.0]))]
+fn dumps_configurations_lammps(
+    configs: &Bound<'_, PyAny>,
+    box_origin: [f64; 3],
+) -> PyResult<Vec<String>> {
+    let configs: Vec<PyConfiguration<'_>> = configs.extract()?;
 
     let refs = configs
         .into_iter()
-        .map(|(time, np_box, np_energe, np_nucleotides)| {
-            (
-                time,
-                np_box.readonly(),
-                np_energe.readonly(),
-                np_nucleotides.readonly(),
-            )
+        .map(|(time, np_box, _, np_nucleotides)| {
+            (time, np_box.readonly(), np_nucleotides.readonly())
         })
         .collect::<Vec<_>>();
 
     let arrays = refs
         .iter()
-        .map(|(time, box_ref, energy_ref, nucleotides_ref)| {
-            (
-                *time,
-                (*box_ref).as_array(),
-                (*energy_ref).as_array(),
-                (*nucleotides_ref).as_array(),
-            )
+        .map(|(time, box_ref, nucleotides_ref)| {
+            (*time, (*box_ref).as_array(), (*nucleotides_ref).as_array())
         })
         .collect::<Vec<_>>();
 
-    let serialized = arrays
-        .par_iter()
-        .map(|(time, box_array, energy_array, nucleotides_array)| {
-            dumps_conf(*time, *box_array, *energy_array, *nucleotides_array)
-        })
+    in_thread_pool(|| {
+        arrays
+            .par_iter()
+            .map(|(time, box_array, nucleotides_array)| {
+                dumps_conf_lammps(*time, *box_array, *nucleotides_array, box_origin)
+            })
+            .collect::<Result<Vec<_>, Error>>()
+    })
+    .map_err(to_py_err)
+}
+
+// PDB coordinates are in angstrom, an oxDNA length unit is 0.8518 nm
+const ANGSTROM_PER_LENGTH_UNIT: f64 = 8.518;
+
+// PDB chain ids, reused in order once there are more strands
+const PDB_CHAIN_IDS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+// atom names and elements of the sites of a nucleotide, a pseudo-atom, or backbone and base
+fn pdb_site_names(sites_per_nucleotide: usize) -> &'static [(&'static str, &'static str)] {
+    match sites_per_nucleotide {
+        1 => &[("C", "C")],
+        _ => &[("P", "P"), ("N", "N")],
+    }
+}
+
+// strands must be (first nucleotide, last nucleotide, sequence) covering the nucleotides in order,
+// returns the number of nucleotides
+fn check_strand_layout(strands: &[(usize, usize, String)]) -> Result<usize, Error> {
+    let mut next_start = 0;
+    for (start, end, sequence) in strands {
+        if *start != next_start || *end + 1 < *start || end + 1 - start != sequence.chars().count()
+        {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "Strand from nucleotide {} to {} with sequence {} does not follow nucleotide {}",
+                    start, end, sequence, next_start
+                ),
+            ));
+        }
+        next_start = end + 1;
+    }
+    Ok(next_start)
+}
+
+// strands are (first nucleotide, last nucleotide, sequence) in nucleotide order, the sites array has
+// sites_per_nucleotide rows per nucleotide, so each strand becomes a chain with a residue per nucleotide
+pub fn dumps_conf_pdb(
+    model: usize,
+    time: u64,
+    sites_array: numpy::ndarray::ArrayView2<'_, f64>,
+    strands: &[(usize, usize, String)],
+    sites_per_nucleotide: usize,
+) -> Result<String, Error> {
+    let n_nucleotides = strands.last().map_or(0, |&(_, end, _)| end + 1);
+    if sites_array.ncols() < 3 || sites_array.nrows() != n_nucleotides * sites_per_nucleotide {
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "Configuration at time {} has {} sites of {} columns, expected {} of 3 for {} nucleotides",
+                time,
+                sites_array.nrows(),
+                sites_array.ncols(),
+                n_nucleotides * sites_per_nucleotide,
+                n_nucleotides
+            ),
+        ));
+    }
+    let site_names = pdb_site_names(sites_per_nucleotide);
+    let mut lines = vec![
+        format!("REMARK   1 TIME {}", time),
+        format!("MODEL     {:>4}", model),
+    ];
+    for (strand_index, (start, end, sequence)) in strands.iter().enumerate() {
+        let chain = PDB_CHAIN_IDS[strand_index % PDB_CHAIN_IDS.len()] as char;
+        for (residue, base) in (*start..=*end).zip(sequence.chars()) {
+            for (site, (name, element)) in site_names.iter().enumerate() {
+                let row = residue * sites_per_nucleotide + site;
+                let position = sites_array.row(row);
+                lines.push(format!(
+                    "ATOM  {:>5} {:<4} {:>3} {}{:>4}    {:>8.3}{:>8.3}{:>8.3}{:>6.2}{:>6.2}          {:>2}",
+                    row + 1,
+                    format!(" {}", name),
+                    base,
+                    chain,
+                    (residue - start + 1) % 10000,
+                    position[0] * ANGSTROM_PER_LENGTH_UNIT,
+                    position[1] * ANGSTROM_PER_LENGTH_UNIT,
+                    position[2] * ANGSTROM_PER_LENGTH_UNIT,
+                    1.0,
+                    0.0,
+                    element
+                ));
+            }
+        }
+        lines.push("TER".to_string());
+    }
+    lines.push("ENDMDL".to_string());
+    lines.push(String::new());
+    Ok(lines.join("\n"))
+}
+
+// bonds along each strand between the first sites of neighboring nucleotides,
+// and between the sites of each nucleotide
+fn pdb_conect_records(strands: &[(usize, usize, String)], sites_per_nucleotide: usize) -> String {
+    let serial = |nucleotide: usize, site: usize| nucleotide * sites_per_nucleotide + site + 1;
+    let mut lines = Vec::new();
+    for &(start, end, _) in strands {
+        for nucleotide in start..=end {
+            let mut bonded = (1..sites_per_nucleotide)
+                .map(|site| serial(nucleotide, site))
+                .collect::<Vec<_>>();
+            if nucleotide < end {
+                bonded.push(serial(nucleotide + 1, 0));
+            }
+            if !bonded.is_empty() {
+                let bonded = bonded
+                    .iter()
+                    .map(|atom| format!("{:>5}", atom))
+                    .collect::<String>();
+                lines.push(format!("CONECT{:>5}{}", serial(nucleotide, 0), bonded));
+            }
+        }
+    }
+    lines.push("END".to_string());
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+pub fn write_confs_pdb(
+    file_path: &str,
+    configs: &[(
+        u64,
+        numpy::ndarray::ArrayView1<'_, f64>,
+        numpy::ndarray::ArrayView2<'_, f64>,
+    )],
+    strands: &[(usize, usize, String)],
+    sites_per_nucleotide: usize,
+) -> Result<(), Error> {
+    let n_sites = check_strand_layout(strands)? * sites_per_nucleotide;
+    // atom serial numbers have 5 digits
+    if n_sites > 99999 {
+        return Err(Error::new(
+            InvalidInput,
+            format!("PDB files hold up to 99999 atoms, got {}", n_sites),
+        ));
+    }
+    let models = in_thread_pool(|| {
+        configs
+            .par_iter()
+            .enumerate()
+            .map(|(index, (time, _, sites_array))| {
+                dumps_conf_pdb(
+                    index + 1,
+                    *time,
+                    *sites_array,
+                    strands,
+                    sites_per_nucleotide,
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()
+    })?;
+    let mut output = std::io::BufWriter::new(File::create(file_path)?);
+    // a single unit cell, boxes of later configurations are left out
+    if let Some((_, box_array, _)) = configs.first() {
+        if box_array.len() < 3 {
+            return Err(Error::new(
+                InvalidInput,
+                format!("Box array needs 3 values, got {}", box_array.len()),
+            ));
+        }
+        writeln!(
+            output,
+            "CRYST1{:>9.3}{:>9.3}{:>9.3}{:>7.2}{:>7.2}{:>7.2} P 1           1",
+            box_array[0] * ANGSTROM_PER_LENGTH_UNIT,
+            box_array[1] * ANGSTROM_PER_LENGTH_UNIT,
+            box_array[2] * ANGSTROM_PER_LENGTH_UNIT,
+            90.0,
+            90.0,
+            90.0
+        )?;
+    }
+    for model in models {
+        output.write_all(model.as_bytes())?;
+    }
+    output.write_all(pdb_conect_records(strands, sites_per_nucleotide).as_bytes())?;
+    output.flush()
+}
+
+#[pyfunction]
+fn write_configurations_pdb(
+    file_path: &str,
+    configs: &Bound<'_, PyAny>,
+    strands: Vec<(usize, usize, String)>,
+    sites_per_nucleotide: usize,
+) -> PyResult<()> {
+    let configs: Vec<PyConfiguration<'_>> = configs.extract()?;
+
+    let refs = configs
+        .into_iter()
+        .map(|(time, np_box, _, np_sites)| (time, np_box.readonly(), np_sites.readonly()))
         .collect::<Vec<_>>();
 
-    Ok(serialized)
+    let arrays = refs
+        .iter()
+        .map(|(time, box_ref, sites_ref)| (*time, (*box_ref).as_array(), (*sites_ref).as_array()))
+        .collect::<Vec<_>>();
+
+    write_confs_pdb(file_path, &arrays, &strands, sites_per_nucleotide).map_err(to_py_err)
+}
+
+#[pyfunction]
+fn write_configuration_lammps_data(
+    file_path: &str,
+    configuration: PyConfiguration<'_>,
+    strands: Vec<(usize, usize, String)>,
+) -> PyResult<()> {
+    let (time, np_box, _, np_nucleotides) = configuration;
+    write_lammps_data(
+        file_path,
+        time,
+        np_box.readonly().as_array(),
+        np_nucleotides.readonly().as_array(),
+        &strands,
+    )
+    .map_err(to_py_err)
 }
 
 #[pymodule]
 fn oxdna_trajectory_reader(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add(
+        "TrajectoryFormatError",
+        m.py().get_type::<TrajectoryFormatError>(),
+    )?;
+    m.add(
+        "MissingHeaderError",
+        m.py().get_type::<MissingHeaderError>(),
+    )?;
+    m.add("BadValueError", m.py().get_type::<BadValueError>())?;
+    m.add(
+        "CountMismatchError",
+        m.py().get_type::<CountMismatchError>(),
+    )?;
     m.add_function(wrap_pyfunction!(read_configurations, m)?)?;
+    m.add_function(wrap_pyfunction!(read_configurations_by_index, m)?)?;
+    m.add_function(wrap_pyfunction!(read_configurations_parallel, m)?)?;
+    m.add_function(wrap_pyfunction!(read_frames, m)?)?;
+    m.add_function(wrap_pyfunction!(read_last, m)?)?;
+    m.add_function(wrap_pyfunction!(read_lammps_configurations, m)?)?;
+    m.add_function(wrap_pyfunction!(find_configuration_by_time, m)?)?;
+    m.add_function(wrap_pyfunction!(read_configurations_from_stdin, m)?)?;
+    m.add_function(wrap_pyfunction!(iter_configurations, m)?)?;
+    m.add_function(wrap_pyfunction!(read_configurations_multi, m)?)?;
+    m.add_function(wrap_pyfunction!(read_configurations_multi_parallel, m)?)?;
+    m.add_function(wrap_pyfunction!(read_raw_lines, m)?)?;
     m.add_function(wrap_pyfunction!(read_indicies, m)?)?;
+    m.add_function(wrap_pyfunction!(read_indicies_mmap, m)?)?;
+    m.add_function(wrap_pyfunction!(build_index, m)?)?;
+    m.add_function(wrap_pyfunction!(save_index, m)?)?;
+    m.add_function(wrap_pyfunction!(load_index, m)?)?;
+    m.add_function(wrap_pyfunction!(truncate_incomplete, m)?)?;
+    m.add_function(wrap_pyfunction!(set_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(get_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(time_deltas, m)?)?;
+    m.add_function(wrap_pyfunction!(read_boxes, m)?)?;
+    m.add_function(wrap_pyfunction!(read_headers, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_trajectory, m)?)?;
+    m.add_function(wrap_pyfunction!(read_topology, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_checksums, m)?)?;
+    m.add_function(wrap_pyfunction!(read_energies, m)?)?;
+    m.add_function(wrap_pyfunction!(centers_of_mass, m)?)?;
+    m.add_function(wrap_pyfunction!(temperature, m)?)?;
+    m.add_function(wrap_pyfunction!(strand_centers, m)?)?;
+    m.add_function(wrap_pyfunction!(read_rmsd, m)?)?;
+    m.add_function(wrap_pyfunction!(end_to_end_vectors, m)?)?;
+    m.add_function(wrap_pyfunction!(read_displacements, m)?)?;
+    m.add_function(wrap_pyfunction!(read_pair_distances, m)?)?;
+    m.add_function(wrap_pyfunction!(clash_count, m)?)?;
+    m.add_function(wrap_pyfunction!(read_position_statistics, m)?)?;
+    m.add_function(wrap_pyfunction!(read_position_bounds, m)?)?;
+    m.add_function(wrap_pyfunction!(oriented_bbox, m)?)?;
+    m.add_function(wrap_pyfunction!(p2_order, m)?)?;
+    m.add_function(wrap_pyfunction!(equilibration_frame, m)?)?;
+    m.add_function(wrap_pyfunction!(check_energy_sum, m)?)?;
+    m.add_function(wrap_pyfunction!(find_out_of_box, m)?)?;
+    m.add_function(wrap_pyfunction!(orientation_norm_range, m)?)?;
+    m.add_function(wrap_pyfunction!(count_mode, m)?)?;
     m.add_function(wrap_pyfunction!(dumps_configurations, m)?)?;
+    m.add_function(wrap_pyfunction!(write_configurations, m)?)?;
+    m.add_function(wrap_pyfunction!(dumps_configurations_positions, m)?)?;
+    m.add_function(wrap_pyfunction!(dumps_configurations_xyz, m)?)?;
+    m.add_function(wrap_pyfunction!(dumps_configurations_lammps, m)?)?;
+    m.add_function(wrap_pyfunction!(write_configurations_pdb, m)?)?;
+    m.add_function(wrap_pyfunction!(write_configuration_lammps_data, m)?)?;
+    m.add_function(wrap_pyfunction!(append_configuration, m)?)?;
+    m.add_function(wrap_pyfunction!(downsample_trajectory, m)?)?;
+    m.add_function(wrap_pyfunction!(merge_trajectories_by_time, m)?)?;
+    m.add_function(wrap_pyfunction!(write_positions_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(write_configurations_xyz, m)?)?;
+    m.add_function(wrap_pyfunction!(read_configurations_mmap_out, m)?)?;
+    m.add_class::<FrameView>()?;
+    m.add_class::<TrajectoryReader>()?;
+    m.add_class::<TrajectoryWriter>()?;
+    m.add_class::<ReadOptions>()?;
+    m.add_class::<ReadResult>()?;
+    m.add_class::<ValidationSummary>()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static FILE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    // file in the temporary directory, removed when dropped along with an index built for it
+    struct TempFile(String);
+
+    impl TempFile {
+        fn new(contents: impl AsRef<[u8]>) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "oxdna_trajectory_reader_{}_{}",
+                std::process::id(),
+                FILE_COUNT.fetch_add(1, Ordering::Relaxed)
+            ));
+            std::fs::write(&path, contents).unwrap();
+            Self(path.to_string_lossy().into_owned())
+        }
+
+        fn path(&self) -> &str {
+            &self.0
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+            let _ = std::fs::remove_file(index::index_path(&self.0));
+        }
+    }
+
+    fn times(configs: &[(u64, Configuration)]) -> Vec<u64> {
+        configs.iter().map(|(_, conf)| conf.time).collect()
+    }
+
+    #[test]
+    fn reads_uppercase_headers_when_case_insensitive() {
+        let file = TempFile::new(
+            "T = 0\nB = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n\
+             T = 100\nB = 10 10 10\nE = 0 0 0\n1 0 0 1 0 0 0 0 1\n",
+        );
+        let options = ParseOptions {
+            case_insensitive_headers: true,
+            ..Default::default()
+        };
+        let configs = read_confs(file.path(), 0, 10, &options).unwrap();
+        assert_eq!(times(&configs), [0, 100]);
+        assert_eq!(configs[1].1.cbox, [10.0; 3]);
+        let ends = configs.iter().map(|(end, _)| *end).collect::<Vec<_>>();
+        assert_eq!(read_offsets(file.path(), 0, 10, &options).unwrap(), ends);
+        let default = ParseOptions::default();
+        assert!(read_confs(file.path(), 0, 10, &default).is_err());
+        assert!(read_offsets(file.path(), 0, 10, &default).is_err());
+    }
+
+    #[test]
+    fn frame_view_parses_on_first_access() {
+        let first = "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n";
+        let second = "t = 100\nb = 10 10 10\nE = 0 0 0\n1 2 3 1 0 0 0 0 1\n";
+        let file = TempFile::new([first, second].concat());
+        let mut view = FrameView::new(file.path().to_string(), first.len() as u64, false);
+        assert!(!view.is_parsed());
+        assert_eq!(view.configuration().unwrap().time, 100);
+        assert!(view.is_parsed());
+        assert_eq!(
+            view.configuration().unwrap().nucleotides[0][..3],
+            [1.0, 2.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn reads_energy_counts_that_differ_between_frames() {
+        let file = TempFile::new(
+            "t = 0\nb = 10 10 10\nE = -1.5 0.5\n0 0 0 1 0 0 0 0 1\n\
+             t = 100\nb = 10 10 10\nE = -1.5 0.5 -1\n0 0 0 1 0 0 0 0 1\n",
+        );
+        let configs = read_confs(file.path(), 0, 10, &ParseOptions::default()).unwrap();
+        assert_eq!(configs[0].1.cenergy, [-1.5, 0.5]);
+        assert_eq!(configs[1].1.cenergy, [-1.5, 0.5, -1.0]);
+        assert_eq!(uniform_energy_count(&configs), None);
+        assert_eq!(uniform_energy_count(&configs[1..]), Some(3));
+    }
+
+    #[test]
+    fn differences_irregular_times() {
+        let file = TempFile::new(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n\
+             t = 100\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n\
+             t = 250\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n\
+             t = 240\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+        );
+        let times = read_times(file.path(), 0, 10, &ParseOptions::default()).unwrap();
+        assert_eq!(time_differences(&times).unwrap(), [100, 150, -10]);
+        assert!(time_differences(&times[..1]).unwrap().is_empty());
+        assert!(time_differences(&[0, u64::MAX]).is_err());
+        assert!(time_differences(&[u64::MAX, 0]).is_err());
+    }
+
+    #[test]
+    fn weights_centers_of_mass_by_mass() {
+        let file =
+            TempFile::new("t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n4 2 0 1 0 0 0 0 1\n");
+        let centers = read_centers_of_mass(file.path(), 0, 10, None).unwrap();
+        assert_eq!(centers, [(0, [2.0, 1.0, 0.0])]);
+        let centers = read_centers_of_mass(file.path(), 0, 10, Some(&[1.0, 3.0])).unwrap();
+        assert_eq!(centers, [(0, [3.0, 1.5, 0.0])]);
+        assert!(read_centers_of_mass(file.path(), 0, 10, Some(&[1.0])).is_err());
+    }
+
+    #[test]
+    fn resumes_reading_from_returned_end_offset() {
+        let text = (0..10)
+            .map(|index| {
+                format!(
+                    "t = {}\nb = 10 10 10\nE = 0 0 0\n{} 0 0 1 0 0 0 0 1\n",
+                    index * 100,
+                    index
+                )
+            })
+            .collect::<String>();
+        let file = TempFile::new(text);
+        let options = ParseOptions::default();
+        let first = read_confs(file.path(), 0, 5, &options).unwrap();
+        let second = read_confs(file.path(), first[4].0, 5, &options).unwrap();
+        let whole = read_confs(file.path(), 0, 10, &options).unwrap();
+        let resumed = first.iter().chain(&second).collect::<Vec<_>>();
+        assert_eq!(resumed.len(), whole.len());
+        for ((end, conf), (whole_end, whole_conf)) in resumed.into_iter().zip(&whole) {
+            assert_eq!((end, conf.time), (whole_end, whole_conf.time));
+            assert_eq!(conf.nucleotides, whole_conf.nucleotides);
+        }
+        let offsets = read_offsets(file.path(), 0, 5, &options).unwrap();
+        let rest = read_offsets(file.path(), offsets[4], 5, &options).unwrap();
+        let ends = whole.iter().map(|(end, _)| *end).collect::<Vec<_>>();
+        assert_eq!([offsets, rest].concat(), ends);
+    }
+
+    #[test]
+    fn finds_first_nucleotide_out_of_box() {
+        let file = TempFile::new(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n1 1 1 1 0 0 0 0 1\n9 9 9 1 0 0 0 0 1\n\
+             t = 100\nb = 10 10 10\nE = 0 0 0\n1 1 1 1 0 0 0 0 1\n1 -1 1 1 0 0 0 0 1\n\
+             t = 200\nb = 10 10 10\nE = 0 0 0\n10 1 1 1 0 0 0 0 1\n1 1 12 1 0 0 0 0 1\n",
+        );
+        assert_eq!(
+            read_out_of_box(file.path(), 0, 10).unwrap(),
+            [(1, 1), (2, 0)]
+        );
+    }
+
+    #[test]
+    fn parses_headers_without_spaces_or_with_extra_spaces() {
+        let lines = |values: [&str; 3]| values.map(str::to_string);
+        let options = ParseOptions::default();
+        let expected = (100, vec![1.0, 2.0, 3.0], vec![0.0, 0.0, 0.0]);
+        let compact = lines(["t=100", "b=1 2 3", "E=0 0 0"]);
+        assert_eq!(
+            Configuration::parse_headers(&compact, &options).unwrap(),
+            expected
+        );
+        let spaced = lines(["  t = 100", "b =  1 2 3 ", "E  = 0 0 0"]);
+        assert_eq!(
+            Configuration::parse_headers(&spaced, &options).unwrap(),
+            expected
+        );
+        // keys in other case only with case insensitive headers
+        let other_case = lines(["T=100", "B = 1 2 3", "e = 0 0 0"]);
+        assert!(Configuration::parse_headers(&other_case, &options).is_err());
+        let case_insensitive = ParseOptions {
+            case_insensitive_headers: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            Configuration::parse_headers(&other_case, &case_insensitive).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn reports_shortest_and_longest_orientation_norms() {
+        let file = TempFile::new(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n0 0 0 0.5 0 0 0 0 1\n\
+             t = 100\nb = 10 10 10\nE = 0 0 0\n0 0 0 0 1 0 0 0 2\n0 0 0 0 0 1 1 0 0\n",
+        );
+        assert_eq!(
+            read_orientation_norm_range(file.path(), 0, 10).unwrap(),
+            [0.5, 1.0, 1.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn reads_from_offset_inside_body_only_without_require_frame_start() {
+        let first = "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n";
+        let second = "t = 100\nb = 10 10 10\nE = 0 0 0\n1 0 0 1 0 0 0 0 1\n";
+        let file = TempFile::new([first, second].concat());
+        let inside_body = first.find("0 0 0 1").unwrap() as u64;
+        let options = ParseOptions::default();
+        let configs = read_confs(file.path(), inside_body, 10, &options).unwrap();
+        assert_eq!(times(&configs), [100]);
+        let required = ParseOptions {
+            require_frame_start: true,
+            ..Default::default()
+        };
+        assert!(read_confs(file.path(), inside_body, 10, &required).is_err());
+        assert!(read_offsets(file.path(), inside_body, 10, &required).is_err());
+        let configs = read_confs(file.path(), first.len() as u64, 10, &required).unwrap();
+        assert_eq!(times(&configs), [100]);
+    }
+
+    #[test]
+    fn reads_files_concurrently_like_sequentially() {
+        let file_a = TempFile::new(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n\
+             t = 100\nb = 10 10 10\nE = 0 0 0\n1 0 0 1 0 0 0 0 1\n\
+             t = 200\nb = 10 10 10\nE = 0 0 0\n2 0 0 1 0 0 0 0 1\n",
+        );
+        let first_b = "t = 5\nb = 8 8 8\nE = 0 0 0\n0 1 0 1 0 0 0 0 1\n";
+        let file_b = TempFile::new(
+            [
+                first_b,
+                "t = 15\nb = 8 8 8\nE = 0 0 0\n0 2 0 1 0 0 0 0 1\n",
+                "t = 25\nb = 8 8 8\nE = 0 0 0\n0 3 0 1 0 0 0 0 1\n",
+            ]
+            .concat(),
+        );
+        let specs = [
+            (file_a.path().to_string(), 0, 2),
+            (file_b.path().to_string(), first_b.len() as u64, 10),
+        ];
+        let options = ParseOptions::default();
+        let mut sequential = Vec::new();
+        for (file_index, (file_path, offset, limit)) in specs.iter().enumerate() {
+            for (end_offset, conf) in read_confs(file_path, *offset, *limit, &options).unwrap() {
+                sequential.push((file_index, end_offset, conf.time, conf.nucleotides));
+            }
+        }
+        let concurrent = read_confs_multi_parallel(&specs, &options)
+            .unwrap()
+            .into_iter()
+            .map(|(file_index, end_offset, conf)| {
+                (file_index, end_offset, conf.time, conf.nucleotides)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(concurrent, sequential);
+        let read = concurrent
+            .iter()
+            .map(|(file_index, _, time, _)| (*file_index, *time))
+            .collect::<Vec<_>>();
+        assert_eq!(read, [(0, 0), (0, 100), (1, 15), (1, 25)]);
+    }
+
+    #[test]
+    fn finds_modal_nucleotide_count_and_outlier_frames() {
+        let text = (0..5)
+            .map(|index| {
+                let n_nucleotides = if index == 3 { 9 } else { 10 };
+                let mut frame = format!("t = {}\nb = 10 10 10\nE = 0 0 0\n", index * 100);
+                for _ in 0..n_nucleotides {
+                    frame.push_str("0 0 0 1 0 0 0 0 1\n");
+                }
+                frame
+            })
+            .collect::<String>();
+        let file = TempFile::new(text);
+        assert_eq!(
+            read_nucleotide_count_mode(file.path(), 0).unwrap(),
+            (10, vec![(3, 9)])
+        );
+    }
+
+    #[test]
+    fn writes_position_rows_after_csv_header() {
+        let file = TempFile::new(
+            "t = 300\nb = 10 10 10\nE = 0 0 0\n1.5 -2 3.25 1 0 0 0 0 1\n4 5 6 1 0 0 0 0 1\n",
+        );
+        let output = TempFile::new("");
+        assert_eq!(write_csv(file.path(), output.path()).unwrap(), 1);
+        let csv = std::fs::read_to_string(output.path()).unwrap();
+        let rows = csv.lines().collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [
+                "frame,time,nucleotide,x,y,z",
+                "0,300,0,1.5,-2,3.25",
+                "0,300,1,4,5,6"
+            ]
+        );
+    }
+
+    #[test]
+    fn computes_p2_order_of_a1_against_axis() {
+        let file = TempFile::new(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 0 0 1 1 0 0\n1 0 0 0 0 -2 1 0 0\n",
+        );
+        assert_eq!(
+            read_p2_order(file.path(), 0, 10, [0.0, 0.0, 3.0]).unwrap(),
+            [1.0]
+        );
+        assert_eq!(
+            read_p2_order(file.path(), 0, 10, [1.0, 0.0, 0.0]).unwrap(),
+            [-0.5]
+        );
+        assert!(read_p2_order(file.path(), 0, 10, [0.0; 3]).is_err());
+    }
+
+    #[test]
+    fn spills_nucleotides_as_native_endian_values() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n",
+            "1 2 3 1 0 0 0 0 1 0 0 0 0 0 0\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n",
+            "4 5 6 1 0 0 0 0 1 0 0 0 0 0 7\n",
+        ));
+        let output = TempFile::new("");
+        let (times, n_nucleotides, n_columns) =
+            spill_confs(file.path(), output.path(), 0, 10).unwrap();
+        assert_eq!((times, n_nucleotides, n_columns), (vec![0, 100], 1, 15));
+        let bytes = std::fs::read(output.path()).unwrap();
+        let values = bytes
+            .chunks_exact(8)
+            .map(|chunk| f64::from_ne_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(values.len(), 2 * 15);
+        assert_eq!(values[..3], [1.0, 2.0, 3.0]);
+        assert_eq!(values[15..18], [4.0, 5.0, 6.0]);
+        assert_eq!(values[29], 7.0);
+
+        let file =
+            TempFile::new("t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n1 1 1 1 0 0 0 0 1\n");
+        let (_, n_nucleotides, n_columns) = spill_confs(file.path(), output.path(), 0, 10).unwrap();
+        assert_eq!((n_nucleotides, n_columns), (2, 9));
+        assert_eq!(std::fs::metadata(output.path()).unwrap().len(), 2 * 9 * 8);
+
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1 0 0 0 0 0 0\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+        ));
+        assert!(spill_confs(file.path(), output.path(), 0, 10).is_err());
+    }
+
+    #[test]
+    fn aligns_bounds_to_principal_axes() {
+        let mut contents = String::from("t = 0\nb = 30 30 30\nE = 0 0 0\n");
+        for i in 0..11 {
+            contents += &format!("{} {} {} 1 0 0 0 0 1\n", i, i, (i % 2) as f64 * 0.5);
+        }
+        let file = TempFile::new(contents);
+        let bounds = read_oriented_bounds(file.path(), 0, 10).unwrap();
+        let (extents, rotation) = bounds[0];
+        assert!((extents[0] - 10.0 * 2f64.sqrt()).abs() < 1e-6);
+        assert!(extents[0] >= extents[1] && extents[1] >= extents[2]);
+        let along_diagonal = (rotation[0][0] + rotation[0][1]) / 2f64.sqrt();
+        assert!((along_diagonal.abs() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn iterates_frames_with_indices_and_start_offsets() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n1 0 0 1 0 0 0 0 1\n",
+            "t = 200\nb = 10 10 10\nE = 0 0 0\n2 0 0 1 0 0 0 0 1\n",
+        ));
+        let options = ParseOptions::default();
+        let ends = read_offsets(file.path(), 0, 10, &options).unwrap();
+        // never dropped, as dropping its buffer would call into Python, which tests do not link
+        let mut reader = std::mem::ManuallyDrop::new(TrajectoryReader {
+            frames: ConfigReader::new(file.path(), 0, SaveLines::All, &options).unwrap(),
+            options,
+            index: 0,
+            reuse_buffer: false,
+            buffer: None,
+        });
+        let mut frames = Vec::new();
+        while let Some((index, config_start, conf)) = reader.next_frame().unwrap() {
+            frames.push((index, config_start, conf.time));
+        }
+        assert_eq!(frames, [(0, 0, 0), (1, ends[0], 100), (2, ends[1], 200)]);
+    }
+
+    #[test]
+    fn reads_by_index_from_start_offsets() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n1 0 0 1 0 0 0 0 1\n",
+            "t = 200\nb = 10 10 10\nE = 0 0 0\n2 0 0 1 0 0 0 0 1\n",
+        ));
+        let options = ParseOptions {
+            require_frame_start: true,
+            ..Default::default()
+        };
+        let ends = read_offsets(file.path(), 0, 10, &options).unwrap();
+        let starts = [0, ends[0], ends[1]];
+        let configs = read_confs_by_index(file.path(), &starts, 0, 2, &options).unwrap();
+        assert_eq!(times(&configs), [0, 100]);
+        let configs = read_confs_by_index(file.path(), &starts, 1, 2, &options).unwrap();
+        assert_eq!(times(&configs), [100, 200]);
+        assert!(read_confs_by_index(file.path(), &starts, 2, 0, &options)
+            .unwrap()
+            .is_empty());
+        assert!(read_confs_by_index(file.path(), &starts, 2, 2, &options).is_err());
+        assert!(read_confs_by_index(file.path(), &[1], 0, 1, &options).is_err());
+    }
+
+    #[test]
+    fn finds_energy_sum_mismatches() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 1.5 1 0.5\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 2 1 0.5\n0 0 0 1 0 0 0 0 1\n",
+        ));
+        assert_eq!(
+            read_energy_mismatches(file.path(), 0, 10, 0.1).unwrap(),
+            [1]
+        );
+        assert!(read_energy_mismatches(file.path(), 0, 10, 1.0)
+            .unwrap()
+            .is_empty());
+        let file = TempFile::new("t = 0\nb = 10 10 10\nE = 1.5 1\n0 0 0 1 0 0 0 0 1\n");
+        assert!(read_energy_mismatches(file.path(), 0, 10, 0.1).is_err());
+    }
+
+    #[test]
+    fn parses_floats_like_str_parse() {
+        for token in [
+            "0.1",
+            "+1",
+            "-0",
+            "1E5",
+            "1e-320",
+            "1.7976931348623157e308",
+            "nan",
+            "inf",
+            "-inf",
+            "0.30000000000000004",
+        ] {
+            let expected = token.parse::<f64>().unwrap();
+            let parsed = Configuration::parse_float(token).unwrap();
+            assert_eq!(parsed.to_bits(), expected.to_bits(), "{}", token);
+        }
+        for token in ["", "1,0", "abc", "1.0.0"] {
+            assert_eq!(Configuration::parse_float(token), None, "{}", token);
+        }
+    }
+
+    #[test]
+    fn reads_nine_and_fifteen_column_nucleotides() {
+        let options = ParseOptions::default();
+        let file =
+            TempFile::new("t = 0\nb = 10 10 10\nE = 0 0 0\n1 2 3 1 0 0 0 0 1\n4 5 6 1 0 0 0 0 1\n");
+        let configs = read_confs(file.path(), 0, 10, &options).unwrap();
+        assert_eq!(
+            configs[0].1.nucleotides,
+            [
+                [1.0, 2.0, 3.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+                [4.0, 5.0, 6.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]
+            ]
+        );
+        let file =
+            TempFile::new("t = 0\nb = 10 10 10\nE = 0 0 0\n1 2 3 1 0 0 0 0 1 7 8 9 10 11 12\n");
+        let configs = read_confs(file.path(), 0, 10, &options).unwrap();
+        assert_eq!(configs[0].1.nucleotides[0].len(), 15);
+        assert_eq!(configs[0].1.nucleotides[0][14], 12.0);
+        let extra = ParseOptions {
+            columns_per_nucleotide: Some(9),
+            extra_columns: true,
+            ..Default::default()
+        };
+        let configs = read_confs(file.path(), 0, 10, &extra).unwrap();
+        assert_eq!(configs[0].1.nucleotides[0].len(), 9);
+        assert_eq!(
+            configs[0].1.extra_columns[0],
+            [7.0, 8.0, 9.0, 10.0, 11.0, 12.0]
+        );
+        let file = TempFile::new(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1 0 0 0 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+        );
+        assert!(read_confs(file.path(), 0, 10, &options).is_err());
+    }
+
+    #[test]
+    fn computes_strand_centers() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n",
+            "0 1 2 1 0 0 0 0 1\n1 1 2 1 0 0 0 0 1\n2 1 2 1 0 0 0 0 1\n",
+        ));
+        assert_eq!(
+            read_strand_centers(file.path(), &[2, 1], 0, 10).unwrap(),
+            [vec![[0.5, 1.0, 2.0], [2.0, 1.0, 2.0]]]
+        );
+        assert!(read_strand_centers(file.path(), &[2, 2], 0, 10).is_err());
+    }
+
+    #[test]
+    fn computes_rmsd_against_reference() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n1 1 1 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n3 0 0 1 0 0 0 0 1\n1 5 1 1 0 0 0 0 1\n",
+        ));
+        let reference = [[0.0; 3], [1.0; 3]];
+        assert_eq!(
+            read_rmsds(file.path(), 0, 10, &reference).unwrap(),
+            [(0, 0.0), (100, 12.5f64.sqrt())]
+        );
+        assert!(read_rmsds(file.path(), 0, 10, &reference[..1]).is_err());
+    }
+
+    #[test]
+    fn fills_a_reused_buffer_in_place() {
+        let mut buffer = numpy::ndarray::Array2::<f64>::zeros((2, 9));
+        let data = buffer.as_ptr();
+        let nucleotides = vec![vec![1.0; 9], vec![2.0; 9]];
+        assert!(fill_rows(buffer.view_mut(), &nucleotides));
+        assert_eq!(buffer.as_ptr(), data);
+        assert_eq!(buffer.row(0).to_vec(), nucleotides[0]);
+        assert_eq!(buffer.row(1).to_vec(), nucleotides[1]);
+        assert!(fill_rows(buffer.view_mut(), &[vec![3.0; 9], vec![4.0; 9]]));
+        assert_eq!(buffer.as_ptr(), data);
+        assert_eq!(buffer[[1, 8]], 4.0);
+        assert!(!fill_rows(buffer.view_mut(), &[vec![5.0; 9]]));
+        assert!(!fill_rows(
+            buffer.view_mut(),
+            &[vec![5.0; 15], vec![5.0; 15]]
+        ));
+        assert_eq!(buffer[[0, 0]], 3.0);
+    }
+
+    #[test]
+    fn computes_end_to_end_directions_across_boundaries() {
+        let file = TempFile::new(
+            "t = 0\nb = 20 20 20\nE = 0 0 0\n1 5 5 1 0 0 0 0 1\n19 5 5 1 0 0 0 0 1\n",
+        );
+        assert_eq!(
+            read_end_to_end_vectors(file.path(), 0, 10, 0, 1).unwrap(),
+            [(0, [-1.0, 0.0, 0.0])]
+        );
+        assert!(read_end_to_end_vectors(file.path(), 0, 10, 0, 0).is_err());
+        assert!(read_end_to_end_vectors(file.path(), 0, 10, 0, 2).is_err());
+        let file =
+            TempFile::new("t = 0\nb = 20 0 20\nE = 0 0 0\n1 1 1 1 0 0 0 0 1\n2 2 2 1 0 0 0 0 1\n");
+        assert!(read_end_to_end_vectors(file.path(), 0, 10, 0, 1).is_err());
+    }
+
+    #[test]
+    fn groups_nucleotides_by_strand_id() {
+        let nucleotides = (0..4).map(|i| vec![i as f64; 9]).collect::<Vec<_>>();
+        let strands = group_by_strand(0, nucleotides.clone(), &[2, 1, 2, 1]).unwrap();
+        assert_eq!(strands.keys().copied().collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(
+            strands[&1],
+            [nucleotides[1].clone(), nucleotides[3].clone()]
+        );
+        assert_eq!(
+            strands[&2],
+            [nucleotides[0].clone(), nucleotides[2].clone()]
+        );
+        assert!(group_by_strand(0, nucleotides, &[1, 1, 2]).is_err());
+    }
+
+    #[test]
+    fn reads_headers_after_body() {
+        let leading = concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n1 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 20 20 20\nE = 0 0 0\n2 0 0 1 0 0 0 0 1\n3 0 0 1 0 0 0 0 1\n",
+        );
+        let trailing = concat!(
+            "0 0 0 1 0 0 0 0 1\n1 0 0 1 0 0 0 0 1\nt = 0\nb = 10 10 10\nE = 0 0 0\n",
+            "2 0 0 1 0 0 0 0 1\n3 0 0 1 0 0 0 0 1\nt = 100\nb = 20 20 20\nE = 0 0 0\n",
+        );
+        let file = TempFile::new(trailing);
+        let options = ParseOptions {
+            header_position: HeaderPosition::Trailing,
+            ..Default::default()
+        };
+        let configs = read_confs(file.path(), 0, 10, &options).unwrap();
+        let leading_file = TempFile::new(leading);
+        let expected = read_confs(leading_file.path(), 0, 10, &ParseOptions::default()).unwrap();
+        assert_eq!(times(&configs), [0, 100]);
+        assert_eq!(configs[1].0, trailing.len() as u64);
+        for ((_, conf), (_, expected)) in configs.iter().zip(&expected) {
+            assert_eq!(conf.nucleotides, expected.nucleotides);
+            assert_eq!(conf.cbox, expected.cbox);
+        }
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn reads_offsets_past_4_gib() {
+        let text = concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n1 0 0 1 0 0 0 0 1\n",
+        );
+        // sparse, so only the frames take up disk space
+        let file = TempFile::new("");
+        let start = u32::MAX as u64 + 10;
+        let mut output = std::fs::OpenOptions::new()
+            .write(true)
+            .open(file.path())
+            .unwrap();
+        output.seek(SeekFrom::Start(start)).unwrap();
+        output.write_all(text.as_bytes()).unwrap();
+        drop(output);
+        let options = ParseOptions::default();
+        let configs = read_confs(file.path(), start, 10, &options).unwrap();
+        let ends = configs.iter().map(|(end, _)| *end).collect::<Vec<_>>();
+        assert_eq!(times(&configs), [0, 100]);
+        assert!(ends[0] > u32::MAX as u64);
+        assert_eq!(ends[1], start + text.len() as u64);
+        assert_eq!(
+            read_offsets(file.path(), start, 10, &options).unwrap(),
+            ends
+        );
+        let reader = ConfigReader::new(file.path(), start, SaveLines::None, &options).unwrap();
+        let starts = reader.map(|result| result.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(starts, [start, ends[0]]);
+        let last = read_last_confs(file.path(), 1, &options).unwrap();
+        assert_eq!(times(&last), [100]);
+    }
+
+    #[test]
+    fn finds_equilibration_from_temperatures() {
+        // velocity (6, 3, 3) gives temperature 9 and (2, 1, 1) gives 1
+        let mut contents = String::new();
+        for index in 0..10 {
+            let velocity = if index == 0 { "6 3 3" } else { "2 1 1" };
+            contents += &format!(
+                "t = {}\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1 {} 0 0 0\n",
+                index * 100,
+                velocity
+            );
+        }
+        let file = TempFile::new(contents);
+        let temperatures = read_temperatures(file.path(), 0, 10, None).unwrap();
+        assert_eq!(temperatures[..2], [(0, 9.0), (100, 1.0)]);
+        assert_eq!(
+            find_equilibration(file.path(), 0, 10, 2, 1.0).unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            find_equilibration(file.path(), 0, 10, 11, 1.0).unwrap(),
+            None
+        );
+        assert!(find_equilibration(file.path(), 0, 10, 0, 1.0).is_err());
+    }
+
+    #[test]
+    fn reorders_and_wraps_nucleotides_when_dumping() {
+        let nucleotides = numpy::ndarray::arr2(&[
+            [0.0, 1.0, 2.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+            [-1.0, 5.0, 5.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+            [30.0, 5.0, 5.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+        ]);
+        let cbox = numpy::ndarray::arr1(&[20.0, 20.0, 20.0]);
+        let energy = numpy::ndarray::arr1(&[1.5, 1.0, 0.5]);
+        let dumped = dumps_transformed_conf(
+            0,
+            cbox.view(),
+            energy.view(),
+            nucleotides.view(),
+            Some(&[2, 0, 1]),
+            true,
+            None,
+        )
+        .unwrap();
+        let positions = dumped
+            .lines()
+            .skip(3)
+            .map(|line| {
+                line.split_whitespace()
+                    .take(3)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(positions, ["10 5 5", "0 1 2", "19 5 5"]);
+        assert!(validate_permutation(&[0, 1, 2], 3).is_ok());
+        assert!(validate_permutation(&[0, 1], 3).is_err());
+        assert!(validate_permutation(&[0, 1, 1], 3).is_err());
+        assert!(validate_permutation(&[0, 1, 3], 3).is_err());
+    }
+
+    #[test]
+    fn drops_a_last_frame_cut_off_at_end_of_file() {
+        let options = ParseOptions::default();
+        let complete = concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n1 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n2 0 0 1 0 0 0 0 1\n3 0 0 1 0 0 0 0 1\n",
+            "t = 200\nb = 10 10 10\nE = 0 0 0\n4 0 0 1 0 0 0 0 1\n5 0 0 1 0 0 0 0 1\n",
+        );
+        let read_dropping_partial = |contents: &str, limit| {
+            let file = TempFile::new(contents);
+            let mut reader = ConfigReader::new(file.path(), 0, SaveLines::All, &options).unwrap();
+            let (configs, discarded) =
+                read_confs_dropping_partial(&mut reader, limit, &options).unwrap();
+            let incomplete = find_incomplete_frame(file.path(), &options).unwrap();
+            (times(&configs), discarded, incomplete)
+        };
+        let last_start = complete.rfind("t = 200").unwrap() as u64;
+
+        // a number cut off in the last line fails to parse
+        let cut = &complete[..complete.len() - 6];
+        assert_eq!(
+            read_dropping_partial(cut, 10),
+            (vec![0, 100], true, Some(last_start))
+        );
+        let file = TempFile::new(cut);
+        assert!(read_confs(file.path(), 0, 10, &options).is_err());
+
+        // one cut between nucleotide lines parses, but has fewer nucleotides than the one before it
+        let cut = &complete[..complete.rfind("5 0 0").unwrap()];
+        assert_eq!(
+            read_dropping_partial(cut, 10),
+            (vec![0, 100], true, Some(last_start))
+        );
+        let file = TempFile::new(cut);
+        assert_eq!(read_confs(file.path(), 0, 10, &options).unwrap().len(), 3);
+
+        assert_eq!(
+            read_dropping_partial(complete, 10),
+            (vec![0, 100, 200], false, None)
+        );
+        assert_eq!(
+            read_dropping_partial(complete, 2),
+            (vec![0, 100], false, None)
+        );
+        // a single configuration has none before it to compare to
+        let single = &complete[..complete.find("t = 100").unwrap()];
+        assert_eq!(read_dropping_partial(single, 10), (vec![0], false, None));
+    }
+
+    #[test]
+    fn reads_energy_headers_as_written() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 1.50  -1e-3 0.500\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 1.5 1 0.5\n0 0 0 1 0 0 0 0 1\n",
+        ));
+        assert_eq!(
+            read_energy_strings(file.path(), 0, 10, &ParseOptions::default()).unwrap(),
+            ["1.50  -1e-3 0.500", "1.5 1 0.5"]
+        );
+    }
+
+    fn count_clashes_brute_force(positions: &[[f64; 3]], cbox: &[f64], radius: f64) -> u64 {
+        let mut count = 0;
+        for (i, a) in positions.iter().enumerate() {
+            for b in &positions[i + 1..] {
+                if minimum_image_distance_squared(a, b, cbox) < radius * radius {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn counts_clashes_with_cell_list_like_every_pair() {
+        let mut state = 12345u64;
+        let mut random = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let cbox = [10.0, 12.0, 15.0];
+        // positions outside the box too, folded in by the periodic boundaries
+        let positions = (0..500)
+            .map(|_| [0, 1, 2].map(|axis| (random() * 3.0 - 1.0) * cbox[axis]))
+            .collect::<Vec<_>>();
+        assert!(positions.len() >= CELL_LIST_MIN_NUCLEOTIDES);
+        for radius in [0.5, 1.0, 2.5, 3.3, 4.0] {
+            let expected = count_clashes_brute_force(&positions, &cbox, radius);
+            assert!(expected > 0);
+            assert_eq!(
+                count_clashes(&positions, &cbox, radius),
+                expected,
+                "{}",
+                radius
+            );
+        }
+        // cells capped by the nucleotide count rather than the box over the radius
+        let huge = [1e12; 3];
+        let far = positions
+            .iter()
+            .map(|position| position.map(|x| x * 1e10))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            count_clashes(&far, &huge, 1e-3),
+            count_clashes_brute_force(&far, &huge, 1e-3)
+        );
+
+        let mut lattice = Vec::new();
+        for x in 0..10 {
+            for y in 0..10 {
+                for z in 0..10 {
+                    lattice.push([x, y, z].map(|i| i as f64 * 2.0));
+                }
+            }
+        }
+        let cbox = [20.0; 3];
+        assert_eq!(count_clashes(&lattice, &cbox, 1.0), 0);
+        // across the boundary from the origin
+        lattice.push([19.5, 0.0, 0.0]);
+        assert_eq!(count_clashes(&lattice, &cbox, 1.0), 1);
+        lattice.push([2.0, 2.0, 2.0]);
+        assert_eq!(count_clashes(&lattice, &cbox, 1.0), 2);
+    }
+
+    #[test]
+    fn reads_clash_counts() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n1 1 1 1 0 0 0 0 1\n1.5 1 1 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n1 1 1 1 0 0 0 0 1\n5 5 5 1 0 0 0 0 1\n",
+        ));
+        assert_eq!(
+            read_clash_counts(file.path(), 0, 10, 1.0).unwrap(),
+            [(0, 1), (100, 0)]
+        );
+        assert!(read_clash_counts(file.path(), 0, 10, 0.0).is_err());
+    }
+
+    #[test]
+    fn checks_energy_counts_against_the_first_frame() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+            "t = 200\nb = 10 10 10\nE = 0 0\n0 0 0 1 0 0 0 0 1\n",
+        ));
+        let configs = read_confs(file.path(), 0, 10, &ParseOptions::default()).unwrap();
+        assert_eq!(check_energy_counts(&configs[..2], None).unwrap(), Some(3));
+        let e = check_energy_counts(&configs, None).unwrap_err();
+        assert_eq!(e.kind(), InvalidInput);
+        assert!(e.to_string().starts_with("Configuration 2 at time 200"));
+        assert_eq!(
+            check_energy_counts(&configs[2..], Some(2)).unwrap(),
+            Some(2)
+        );
+        let e = check_energy_counts(&configs, Some(2)).unwrap_err();
+        assert!(e.to_string().starts_with("Configuration 0 at time 0"));
+        assert_eq!(check_energy_counts(&[], None).unwrap(), None);
+    }
+
+    #[test]
+    fn computes_position_mean_and_variance() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n1 2 3 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n2 0 4 1 0 0 0 0 1\n1 2 3 1 0 0 0 0 1\n",
+        ));
+        let (mean, variance) = read_position_mean_variance(file.path(), 0, 10).unwrap();
+        assert_eq!(mean, [[1.0, 0.0, 2.0], [1.0, 2.0, 3.0]]);
+        assert_eq!(variance, [[1.0, 0.0, 4.0], [0.0; 3]]);
+
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\n",
+        ));
+        let e = read_position_mean_variance(file.path(), 0, 10).unwrap_err();
+        assert_eq!(e.kind(), InvalidInput);
+        assert!(e.to_string().contains("energy"), "{}", e);
+        assert!(e.to_string().contains("byte offset 68"), "{}", e);
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n0 0 0 1 0 0 0 0 1\n",
+        ));
+        assert!(read_position_mean_variance(file.path(), 0, 10).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_box_edges_when_validating() {
+        let options = ParseOptions {
+            validate_box: true,
+            ..Default::default()
+        };
+        let file = TempFile::new("t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n");
+        assert!(read_confs(file.path(), 0, 10, &options).is_ok());
+        for cbox in ["0 10 10", "10 -1 10", "10 10 inf", "nan 10 10"] {
+            let file = TempFile::new(format!(
+                "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n\
+                 t = 100\nb = {}\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+                cbox
+            ));
+            let e = Error::from(read_confs(file.path(), 0, 10, &options).unwrap_err());
+            assert_eq!(e.kind(), InvalidInput);
+            assert!(e.to_string().contains("at time 100"), "{}", e);
+            let configs = read_confs(file.path(), 0, 10, &ParseOptions::default()).unwrap();
+            assert_eq!(configs.len(), 2);
+        }
+    }
+
+    #[test]
+    fn reads_crlf_line_endings() {
+        let text = "t = 0\r\nb = 20 20 20\r\nE = 1.5 1 0.5\r\n0 1 2 1 0 0 0 0 1\r\n\
+                    t = 100\r\nb = 20 20 20\r\nE = 1.5 1 0.5\r\n3 1 2 1 0 0 0 0 1\r\n";
+        let file = TempFile::new(text);
+        let options = ParseOptions::default();
+        let configs = read_confs(file.path(), 0, 10, &options).unwrap();
+        assert_eq!(times(&configs), [0, 100]);
+        let (_, conf) = &configs[0];
+        assert_eq!(conf.cbox, [20.0; 3]);
+        assert_eq!(conf.cenergy, [1.5, 1.0, 0.5]);
+        assert_eq!(
+            conf.nucleotides[0],
+            [0.0, 1.0, 2.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]
+        );
+        assert_eq!(configs[1].0, text.len() as u64);
+        assert_eq!(
+            read_offsets(file.path(), 0, 10, &options).unwrap(),
+            [text.find("t = 100").unwrap() as u64, text.len() as u64]
+        );
+        assert_eq!(
+            read_energy_strings(file.path(), 0, 1, &options).unwrap(),
+            ["1.5 1 0.5"]
+        );
+    }
+
+    #[test]
+    fn reads_body_offsets_after_headers() {
+        let text = concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n1 0 0 1 0 0 0 0 1\n",
+        );
+        let file = TempFile::new(text);
+        let options = ParseOptions::default();
+        let second = text.find("t = 100").unwrap() as u64;
+        let header_length = "t = 0\nb = 10 10 10\nE = 0 0 0\n".len() as u64;
+        assert_eq!(
+            read_offsets_with_body(file.path(), 0, 10, &options).unwrap(),
+            [
+                (second, header_length),
+                (text.len() as u64, second + header_length + 2)
+            ]
+        );
+        assert_eq!(
+            read_offsets_with_body(file.path(), second, 1, &options).unwrap(),
+            [(text.len() as u64, second + header_length + 2)]
+        );
+    }
+
+    #[test]
+    fn merges_trajectories_by_time() {
+        let a = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+            "t = 200\nb = 10 10 10\nE = 0 0 0\n2 0 0 1 0 0 0 0 1\n",
+        ));
+        // no trailing newline
+        let b = TempFile::new(concat!(
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n1 0 0 1 0 0 0 0 1\n",
+            "t = 200\nb = 10 10 10\nE = 0 0 0\n3 0 0 1 0 0 0 0 1",
+        ));
+        let output = TempFile::new("");
+        assert_eq!(merge_by_time(a.path(), b.path(), output.path()).unwrap(), 4);
+        let configs = read_confs(output.path(), 0, 10, &ParseOptions::default()).unwrap();
+        assert_eq!(times(&configs), [0, 100, 200, 200]);
+        let first_columns = configs
+            .iter()
+            .map(|(_, conf)| conf.nucleotides[0][0])
+            .collect::<Vec<_>>();
+        assert_eq!(first_columns, [0.0, 1.0, 2.0, 3.0]);
+        assert!(std::fs::read_to_string(output.path())
+            .unwrap()
+            .ends_with("3 0 0 1 0 0 0 0 1\n"));
+    }
+
+    #[test]
+    fn computes_displacements_between_consecutive_frames() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n1 1 1 1 0 0 0 0 1\n5 5 5 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n9 1 1 1 0 0 0 0 1\n5 8 9 1 0 0 0 0 1\n",
+            "t = 200\nb = 10 10 10\nE = 0 0 0\n9 1 1 1 0 0 0 0 1\n5 8 9 1 0 0 0 0 1\n",
+        ));
+        assert_eq!(
+            read_displacement_rows(file.path(), 0, 10, false).unwrap(),
+            (2, vec![vec![8.0, 5.0], vec![0.0, 0.0]])
+        );
+        // the minimum image of 8 along x is 2 the other way
+        assert_eq!(
+            read_displacement_rows(file.path(), 0, 10, true).unwrap(),
+            (2, vec![vec![2.0, 5.0], vec![0.0, 0.0]])
+        );
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n1 1 1 1 0 0 0 0 1\n5 5 5 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n1 1 1 1 0 0 0 0 1\n",
+        ));
+        assert!(read_displacement_rows(file.path(), 0, 10, false).is_err());
+    }
+
+    #[test]
+    fn reads_boxes_over_time() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 11 12\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10.5 11 12\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+        ));
+        let options = ParseOptions::default();
+        assert_eq!(
+            read_box_series(file.path(), 0, 10, &options).unwrap(),
+            [(0, [10.0, 11.0, 12.0]), (100, [10.5, 11.0, 12.0])]
+        );
+        assert_eq!(
+            read_box_series(file.path(), 0, 1, &options).unwrap().len(),
+            1
+        );
+        let file = TempFile::new("t = 0\nb = 10 11\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n");
+        assert!(read_box_series(file.path(), 0, 10, &options).is_err());
+    }
+
+    #[test]
+    fn computes_minimum_image_pair_distances() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n",
+            "1 1 1 1 0 0 0 0 1\n4 5 1 1 0 0 0 0 1\n9 1 1 1 0 0 0 0 1\n",
+        ));
+        assert_eq!(
+            read_pair_distance_rows(file.path(), 0, 10, &[(0, 1), (0, 2), (2, 0)]).unwrap(),
+            [vec![5.0, 2.0, 2.0]]
+        );
+        assert!(read_pair_distance_rows(file.path(), 0, 10, &[(0, 3)]).is_err());
+    }
+
+    #[test]
+    fn reports_problems_of_each_frame() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n0 0 0 1 0 0 0 0 1\n",
+            "t = 200\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n0 0 0 1 0 0 0 0 1\n",
+            "t = 300\nb = 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n0 0 0 1 0 0 0 0 1\n",
+            "t = 400\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+        ));
+        let options = ParseOptions::default();
+        let summary = validate_frames(file.path(), 0, 10, None, &options).unwrap();
+        assert_eq!(summary.frames, 5);
+        assert_eq!(summary.nucleotide_count, Some(2));
+        assert_eq!(
+            (summary.first_time, summary.last_time),
+            (Some(0), Some(400))
+        );
+        let indices = summary
+            .problems
+            .iter()
+            .map(|(index, _)| *index)
+            .collect::<Vec<_>>();
+        assert_eq!(indices, [2, 3, 4]);
+        assert!(summary.problems[0]
+            .1
+            .contains("does not come after time 200"));
+        assert!(summary.problems[1].1.contains("box"));
+        assert!(summary.problems[2]
+            .1
+            .contains("has 1 nucleotides, expected 2"));
+
+        let summary = validate_frames(file.path(), 0, 2, Some(1), &options).unwrap();
+        assert_eq!(summary.frames, 2);
+        assert_eq!(summary.problems.len(), 2);
+    }
+
+    #[test]
+    fn rejects_compressed_files_outside_read_configurations() {
+        let file = TempFile::new([0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00]);
+        let options = ParseOptions::default();
+        let e = Error::from(read_confs(file.path(), 0, 10, &options).unwrap_err());
+        assert_eq!(e.kind(), InvalidInput);
+        assert!(e.to_string().contains("gzip"));
+        assert!(read_offsets(file.path(), 0, 10, &options).is_err());
+        assert!(read_last_confs(file.path(), 1, &options).is_err());
+    }
+
+    #[test]
+    fn reads_seekable_zstd_from_any_offset() {
+        let data = concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n1 0 0 1 0 0 0 0 1\n",
+            "t = 200\nb = 10 10 10\nE = 0 0 0\n2 0 0 1 0 0 0 0 1\n",
+        )
+        .as_bytes();
+        // frames stored as is, read with an identity decompressor standing in for zstd
+        let sizes = [40, 0, 60, data.len() - 100];
+        let mut entries = Vec::new();
+        for size in sizes {
+            entries.extend((size as u32).to_le_bytes());
+            entries.extend((size as u32).to_le_bytes());
+        }
+        let table_size = ZSTD_SEEK_TABLE_HEADER + entries.len() as u64 + ZSTD_SEEK_TABLE_FOOTER;
+        let mut bytes = data.to_vec();
+        bytes.extend(ZSTD_SKIPPABLE_SEEK_TABLE_MAGIC.to_le_bytes());
+        bytes.extend(((table_size - ZSTD_SEEK_TABLE_HEADER) as u32).to_le_bytes());
+        bytes.extend(entries);
+        bytes.extend((sizes.len() as u32).to_le_bytes());
+        bytes.push(0);
+        bytes.extend(ZSTD_SEEKABLE_MAGIC.to_le_bytes());
+        let file = TempFile::new(&bytes);
+        let frames = read_zstd_seek_table(&mut File::open(file.path()).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(frames.len(), sizes.len() + 1);
+        assert_eq!(frames[4], (data.len() as u64, data.len() as u64));
+        let open = |offset: u64| {
+            let identity: DecompressFrame = Box::new(|compressed, _| Ok(compressed.to_vec()));
+            SeekableZstdReader::new(
+                File::open(file.path()).unwrap(),
+                frames.clone(),
+                identity,
+                offset,
+            )
+            .unwrap()
+        };
+        for offset in 0..=data.len() {
+            let mut read = Vec::new();
+            open(offset as u64).read_to_end(&mut read).unwrap();
+            assert_eq!(read, data[offset..], "{}", offset);
+        }
+
+        let uncompressed = TempFile::new(data);
+        let options = ParseOptions::default();
+        let ends = read_offsets(uncompressed.path(), 0, 10, &options).unwrap();
+        let reader = LineReader::from_reader(BufReader::new(open(ends[0])), ends[0]);
+        let mut reader = ConfigReader::from_reader(reader, SaveLines::All, &options);
+        let configs = read_confs_from(&mut reader, 10, &options).unwrap();
+        assert_eq!(times(&configs), [100, 200]);
+        assert_eq!(
+            configs.iter().map(|(end, _)| *end).collect::<Vec<_>>(),
+            ends[1..]
+        );
+        assert!(
+            read_zstd_seek_table(&mut File::open(uncompressed.path()).unwrap())
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn reads_last_configurations_with_leading_headers_only() {
+        let file = TempFile::new(concat!(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n",
+            "t = 100\nb = 10 10 10\nE = 0 0 0\n1 0 0 1 0 0 0 0 1\n",
+            "t = 200\nb = 10 10 10\nE = 0 0 0\n2 0 0 1 0 0 0 0 1\n",
+        ));
+        let options = ParseOptions::default();
+        assert_eq!(
+            times(&read_last_confs(file.path(), 2, &options).unwrap()),
+            [100, 200]
+        );
+        assert_eq!(
+            times(&read_last_confs(file.path(), 5, &options).unwrap()),
+            [0, 100, 200]
+        );
+        let trailing = ParseOptions {
+            header_position: HeaderPosition::Trailing,
+            ..Default::default()
+        };
+        let e = read_last_confs(file.path(), 1, &trailing).unwrap_err();
+        assert_eq!(e.kind(), InvalidInput);
+    }
+
+    #[test]
+    fn writes_xyz_files_like_dumped_frames() {
+        let trajectory = TempFile::new(
+            "t = 0\nb = 10 10 10\nE = 0 0 0\n0 0 0 1 0 0 0 0 1\n2 0 0 1 0 0 0 0 1\n4 0 0 1 0 0 0 0 1\n",
+        );
+        let topology = TempFile::new("3 2\n1 A -1 1\n1 T 0 -1\n2 G -1 -1\n");
+        let output = TempFile::new("");
+        let options = ParseOptions::default();
+        let conf = read_confs(trajectory.path(), 0, 1, &options)
+            .unwrap()
+            .remove(0)
+            .1;
+        let positions = numpy::ndarray::Array2::from_shape_fn((3, 3), |(row, column)| {
+            conf.nucleotides[row][column]
+        });
+
+        write_xyz(
+            trajectory.path(),
+            output.path(),
+            topology.path(),
+            0,
+            10,
+            false,
+        )
+        .unwrap();
+        let written = std::fs::read_to_string(output.path()).unwrap();
+        assert_eq!(written, "3\nt = 0\nA 0 0 0\nT 2 0 0\nG 4 0 0\n");
+        assert_eq!(
+            dumps_conf_xyz(0, positions.view(), &["A", "T", "G"]).unwrap(),
+            written
+        );
+        assert_eq!(
+            dumps_conf_xyz(0, positions.view(), &["C"; 3]).unwrap(),
+            "3\nt = 0\nC 0 0 0\nC 2 0 0\nC 4 0 0\n"
+        );
+        assert!(dumps_conf_xyz(0, positions.view(), &["C"]).is_err());
+
+        write_xyz(
+            trajectory.path(),
+            output.path(),
+            topology.path(),
+            0,
+            10,
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(output.path()).unwrap(),
+            "2\nt = 0\nS1 1 0 0\nS2 4 0 0\n"
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Error, ErrorKind::InvalidInput};
+
+// nucleotides in file order, the same for both topology formats
+pub struct Topology {
+    pub strand_ids: Vec<i64>,
+    pub bases: Vec<String>,
+    // 3' and 5' neighbor indicies, -1 at strand ends
+    pub neighbors: Vec<[i64; 2]>,
+    // whether each strand is closed, in increasing order of strand id
+    pub circular: Vec<bool>,
+}
+
+impl Topology {
+    // nucleotides in each strand, strands being contiguous runs of nucleotides with the same strand id
+    pub fn strand_lengths(&self) -> Result<Vec<usize>, Error> {
+        let mut strands: Vec<(i64, usize)> = Vec::new();
+        for &strand_id in &self.strand_ids {
+            match strands.last_mut() {
+                Some((last_id, length)) if *last_id == strand_id => *length += 1,
+                _ => {
+                    if strands.iter().any(|&(id, _)| id == strand_id) {
+                        return Err(Error::new(
+                            InvalidInput,
+                            format!("Nucleotides of strand {} are not contiguous", strand_id),
+                        ));
+                    }
+                    strands.push((strand_id, 1));
+                }
+            }
+        }
+        Ok(strands.into_iter().map(|(_, length)| length).collect())
+    }
+}
+
+// classic topology has a `n_nucleotides n_strands` header then one
+// `strand_id base 3'_neighbor 5'_neighbor` line per nucleotide, listed 3' to 5'.
+// oxDNA 3 topology has a `n_nucleotides n_strands 5->3` header then one line per strand,
+// its sequence 5' to 3' followed by `key=value` attributes such as `circular=true`
+pub fn read_topology_file(topology_path: &str) -> Result<Topology, Error> {
+    let invalid = |line: &str| {
+        Error::new(
+            InvalidInput,
+            format!("Invalid topology line in \"{}\": {}", topology_path, line),
+        )
+    };
+    let mut lines = BufReader::new(File::open(topology_path)?).lines();
+    let header = lines.next().transpose()?.unwrap_or_default();
+    let (counts, new_format) = match header.split_whitespace().collect::<Vec<_>>()[..] {
+        [n_nucleotides, n_strands] => ([n_nucleotides, n_strands], false),
+        [n_nucleotides, n_strands, "5->3"] => ([n_nucleotides, n_strands], true),
+        _ => return Err(invalid(&header)),
+    };
+    let [n_nucleotides, n_strands] = counts;
+    let parse = |token: &str| token.parse::<usize>().map_err(|_| invalid(&header));
+    let (n_nucleotides, n_strands) = (parse(n_nucleotides)?, parse(n_strands)?);
+    let mut topology = Topology {
+        strand_ids: Vec::with_capacity(n_nucleotides),
+        bases: Vec::with_capacity(n_nucleotides),
+        neighbors: Vec::with_capacity(n_nucleotides),
+        circular: Vec::new(),
+    };
+    for line in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if new_format {
+            push_strand(&mut topology, &line).ok_or_else(|| invalid(&line))?;
+        } else {
+            push_classic_nucleotide(&mut topology, &line, n_nucleotides)
+                .ok_or_else(|| invalid(&line))?;
+        }
+    }
+    let mut distinct_ids = topology.strand_ids.clone();
+    distinct_ids.sort_unstable();
+    distinct_ids.dedup();
+    if topology.strand_ids.len() != n_nucleotides || distinct_ids.len() != n_strands {
+        return Err(Error::new(
+            InvalidInput,
+            format!(
+                "Topology \"{}\" declares {} nucleotides in {} strands, found {} in {}",
+                topology_path,
+                n_nucleotides,
+                n_strands,
+                topology.strand_ids.len(),
+                distinct_ids.len()
+            ),
+        ));
+    }
+    // a strand is closed when none of its nucleotides is an end
+    topology.circular = distinct_ids
+        .iter()
+        .map(|&id| {
+            topology
+                .strand_ids
+                .iter()
+                .zip(&topology.neighbors)
+                .filter(|(strand_id, _)| **strand_id == id)
+                .all(|(_, neighbors)| !neighbors.contains(&-1))
+        })
+        .collect();
+    Ok(topology)
+}
+
+fn push_classic_nucleotide(
+    topology: &mut Topology,
+    line: &str,
+    n_nucleotides: usize,
+) -> Option<()> {
+    let [strand_id, base, neighbor_3, neighbor_5] = line.split_whitespace().collect::<Vec<_>>()[..]
+    else {
+        return None;
+    };
+    let neighbors = [neighbor_3.parse().ok()?, neighbor_5.parse().ok()?];
+    if neighbors
+        .iter()
+        .any(|&neighbor| neighbor < -1 || neighbor >= n_nucleotides as i64)
+    {
+        return None;
+    }
+    topology.strand_ids.push(strand_id.parse().ok()?);
+    topology.bases.push(base.to_string());
+    topology.neighbors.push(neighbors);
+    Some(())
+}
+
+fn push_strand(topology: &mut Topology, line: &str) -> Option<()> {
+    let mut tokens = line.split_whitespace();
+    let sequence = tokens.next()?;
+    let mut circular = false;
+    for token in tokens {
+        let (key, value) = token.split_once('=')?;
+        if key == "circular" {
+            circular = value.parse().ok()?;
+        }
+    }
+    let bases = split_sequence(sequence)?;
+    let strand_id = topology.strand_ids.last().map_or(1, |id| id + 1);
+    let start = topology.bases.len() as i64;
+    let end = start + bases.len() as i64 - 1;
+    for index in start..=end {
+        // listed 5' to 3', so the 3' neighbor is the next nucleotide
+        let neighbor_3 = match index {
+            index if index < end => index + 1,
+            _ if circular => start,
+            _ => -1,
+        };
+        let neighbor_5 = match index {
+            index if index > start => index - 1,
+            _ if circular => end,
+            _ => -1,
+        };
+        topology.strand_ids.push(strand_id);
+        topology.neighbors.push([neighbor_3, neighbor_5]);
+    }
+    topology.bases.extend(bases);
+    Some(())
+}
+
+// one base per letter, except bases given by a number in parentheses such as `(-3)`
+fn split_sequence(sequence: &str) -> Option<Vec<String>> {
+    let mut bases = Vec::new();
+    let mut rest = sequence;
+    while let Some(first) = rest.chars().next() {
+        let length = match first {
+            '(' => rest.find(')')? + 1,
+            ')' => return None,
+            _ => first.len_utf8(),
+        };
+        let (base, remaining) = rest.split_at(length);
+        bases.push(base.trim_matches(|c| c == '(' || c == ')').to_string());
+        rest = remaining;
+    }
+    if bases.is_empty() {
+        return None;
+    }
+    Some(bases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static FILE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    // reads contents through a file in the temporary directory, removed afterwards
+    fn read_topology(contents: &str) -> Result<Topology, Error> {
+        let path = std::env::temp_dir().join(format!(
+            "oxdna_topology_{}_{}",
+            std::process::id(),
+            FILE_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&path, contents).unwrap();
+        let topology = read_topology_file(&path.to_string_lossy());
+        std::fs::remove_file(&path).unwrap();
+        topology
+    }
+
+    #[test]
+    fn reads_classic_topology() {
+        let topology = read_topology("3 2\n1 A -1 1\n1 G 0 -1\n2 T -1 -1\n").unwrap();
+        assert_eq!(topology.strand_ids, [1, 1, 2]);
+        assert_eq!(topology.bases, ["A", "G", "T"]);
+        assert_eq!(topology.neighbors, [[-1, 1], [0, -1], [-1, -1]]);
+        assert_eq!(topology.circular, [false, false]);
+        assert_eq!(topology.strand_lengths().unwrap(), [2, 1]);
+    }
+
+    #[test]
+    fn rejects_invalid_topologies() {
+        let interleaved = read_topology("3 2\n1 A -1 -1\n2 T -1 -1\n1 G -1 -1\n").unwrap();
+        assert!(interleaved.strand_lengths().is_err());
+        assert!(read_topology("3 1\n1 A -1 1\n1 G 0 -1\n").is_err());
+        assert!(read_topology("2 1\n1 A -1 2\n1 G 0 -1\n").is_err());
+    }
+
+    #[test]
+    fn reads_oxdna3_topology() {
+        let topology = read_topology("4 2 5->3\nAC(-3) circular=true\nT type=DNA\n").unwrap();
+        assert_eq!(topology.strand_ids, [1, 1, 1, 2]);
+        assert_eq!(topology.bases, ["A", "C", "-3", "T"]);
+        assert_eq!(topology.neighbors, [[1, 2], [2, 0], [0, 1], [-1, -1]]);
+        assert_eq!(topology.circular, [true, false]);
+        assert_eq!(topology.strand_lengths().unwrap(), [3, 1]);
+        assert!(read_topology("2 1 5->3\nA)C\n").is_err());
+    }
+}